@@ -0,0 +1,119 @@
+use super::*;
+use crate::provider::{Action, Category, Finding};
+
+/// What to do with parameters that look like trackers on sites without a provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Heuristics {
+    /// Sites without a provider are not analyzed
+    #[default]
+    Off,
+    /// Suspicious parameters are reported, but not removed
+    Report,
+    /// Suspicious parameters are reported and removed
+    Remove,
+}
+
+/// Confidence from which a parameter is reported as a likely tracker
+pub const MIN_CONFIDENCE: f32 = 0.5;
+
+/// Returns the query parameters of the URL that are likely trackers
+pub(crate) fn analyze(url: &Url) -> Vec<Finding> {
+    url.query_pairs()
+        .filter_map(|(name, value)| {
+            let (category, confidence) = score(&name, &value);
+            if confidence < MIN_CONFIDENCE {
+                return None;
+            }
+            Some(Finding {
+                name: name.into_owned(),
+                value: value.into_owned(),
+                category,
+                action: Action::Flagged,
+                confidence,
+                provider: String::new(),
+            })
+        })
+        .collect()
+}
+
+/// Estimates how likely a query parameter is used for tracking, from `0.0` to `1.0`.
+/// The name alone or a random looking value alone stay below [`MIN_CONFIDENCE`], a value
+/// that looks like an email, hash or base64 blob or a random value of a parameter named
+/// like a click, session or referrer ID crosses it.
+pub fn score(name: &str, value: &str) -> (Category, f32) {
+    let name = name.to_ascii_lowercase();
+    let (category, name_score) = if name.ends_with("clid") || name.starts_with("utm_") {
+        (Category::Analytics, 0.4)
+    } else if name == "ref" || name.starts_with("ref_") || name.ends_with("_ref") {
+        (Category::Referral, 0.3)
+    } else if name.len() > 2 && name.ends_with("id") {
+        (Category::Unknown, 0.2)
+    } else {
+        (Category::Unknown, 0.0)
+    };
+
+    let value_score = if looks_like_email(value) {
+        0.8
+    } else if looks_like_hash(value) {
+        0.7
+    } else if looks_like_base64(value) {
+        0.6
+    } else if looks_random(value) {
+        0.4
+    } else {
+        0.0
+    };
+
+    // Value signals are enough on their own, name signals only add to them
+    let confidence = 1.0 - (1.0 - name_score) * (1.0 - value_score);
+    (category, confidence)
+}
+
+fn looks_like_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        }
+        None => false,
+    }
+}
+
+/// Hex encoded MD5, SHA-1 or SHA-256 hashes
+fn looks_like_hash(value: &str) -> bool {
+    matches!(value.len(), 32 | 40 | 64) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn looks_like_base64(value: &str) -> bool {
+    value.len() >= 24
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_'))
+        && value.chars().any(|c| c.is_ascii_uppercase())
+        && value.chars().any(|c| c.is_ascii_lowercase())
+        && value.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Long values with high entropy, text like search queries is never random
+fn looks_random(value: &str) -> bool {
+    value.len() >= 16 && !value.contains([' ', '+']) && entropy(value) >= 3.5
+}
+
+/// Shannon entropy in bits per character
+fn entropy(value: &str) -> f32 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = value.chars().count() as f32;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f32 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
@@ -1,37 +1,55 @@
 //! This crate is intended to remove tracking tokens from URLs contained in any text input.
 //! The crate can either substitute the URLs in place or return a Vec of cleaned urls to a given text input.
 
+mod heuristics;
 mod parsing_core;
 mod parsing_params;
+mod provider;
 
 #[cfg(test)]
 mod tests;
 
 use linkify::{LinkFinder, LinkKind};
-use parsing_core::Parser;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use url::Url;
 
+pub use heuristics::Heuristics;
+pub use parsing_core::{Parser, Report};
+pub use parsing_params::*;
+pub use provider::{
+    Action, Category, Cleaned, Finding, PathRule, Provider, Redirect, Rule, Strictness,
+    TrackingParam,
+};
+
 /// Takes any String as input, parses URLs, returns either `None` if no tracking tokens
 /// were found. Otherwise returns `Some(Vec<String>)` of all sanitized URLs
 pub fn clean_urls_from_any_text(input: &str) -> Option<Vec<String>> {
     let parser = Parser::new();
-    let cleaned_urls = parser.parse_any_text(input);
-    if cleaned_urls.is_none() {
+    let cleaned_urls: Vec<String> = parser
+        .parse_any_text(input)?
+        .into_iter()
+        .filter(Report::is_changed)
+        .map(|report| report.cleaned)
+        .collect();
+    if cleaned_urls.is_empty() {
         return None;
     }
-    cleaned_urls.map(|urls| {
-        urls.into_iter()
-            .map(|(cleaned_url, _)| cleaned_url)
-            .collect()
-    })
+    Some(cleaned_urls)
 }
 
 /// Same as clean_urls_from_any_text, but returns Tuples of the sanitized URL and the part that was removed
 pub fn clean_urls_and_get_removed_part(input: &str) -> Option<Vec<(String, String)>> {
     let parser = Parser::new();
-    let cleaned_urls = parser.parse_any_text(input);
-    cleaned_urls
+    let cleaned_urls: Vec<(String, String)> = parser
+        .parse_any_text(input)?
+        .into_iter()
+        .filter(Report::is_changed)
+        .map(|report| (report.cleaned, report.removed))
+        .collect();
+    if cleaned_urls.is_empty() {
+        return None;
+    }
+    Some(cleaned_urls)
 }
 
 /// Parses any (mutable) String and sanitizes URLs containing tracking tokens in place
@@ -1,154 +1,602 @@
 use super::*;
-use crate::parsing_params::*;
+use crate::heuristics::{self, Heuristics};
+use crate::parsing_params::{builtin_providers, MASTODON};
+use crate::provider::{Action, Category, Cleaned, Finding, Provider, Strictness, TrackingParam};
 
+/// Finds URLs in text and removes tracking tokens from them
 pub struct Parser {
     finder: LinkFinder,
+    url_must_have_scheme: bool,
+    /// Registered providers with their priority, sorted by descending priority
+    providers: Vec<(i32, Box<dyn Provider>)>,
+    disabled_providers: HashSet<String>,
+    strictness: Strictness,
+    provider_strictness: HashMap<String, Strictness>,
+    keep_list_providers: HashSet<String>,
+    heuristics: Heuristics,
+    report_share_links: bool,
+}
+
+/// Describes what was removed from or flagged in a URL found in the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// The URL as it was found in the input
+    pub original: String,
+    /// The cleaned URL, same as `original` if parameters were only flagged
+    pub cleaned: String,
+    /// The characters removed from the original URL
+    pub removed: String,
+    /// Name of the site provider matching the URL. If no site provider matched, the
+    /// provider of the first finding, e.g. `heuristics` or a global provider.
+    pub provider: String,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Returns true if anything was removed from the URL
+    pub fn is_changed(&self) -> bool {
+        self.cleaned != self.original
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Parser {
     pub fn new() -> Self {
         let mut finder = LinkFinder::new();
         finder.kinds(&[LinkKind::Url]);
-        Self { finder }
+        let mut parser = Self {
+            finder,
+            url_must_have_scheme: true,
+            providers: builtin_providers()
+                .into_iter()
+                .map(|provider| (0, provider))
+                .collect(),
+            disabled_providers: HashSet::new(),
+            strictness: Strictness::default(),
+            provider_strictness: HashMap::new(),
+            keep_list_providers: HashSet::new(),
+            heuristics: Heuristics::default(),
+            report_share_links: true,
+        };
+        // Path shapes only decide for hosts no other provider claims
+        parser.register_provider(MASTODON, -1);
+        parser
     }
 
-    pub fn parse_any_text(&self, input: &str) -> Option<Vec<(String, String)>> {
-        let links: Vec<_> = self.finder.links(input).collect();
-        if links.is_empty() {
-            return None;
+    /// Sets whether URLs need a scheme to be detected (default `true`).
+    ///
+    /// If set to `false`, links like `youtu.be/abc?si=123` are cleaned as well and
+    /// written back without a scheme.
+    pub fn url_must_have_scheme(&mut self, url_must_have_scheme: bool) -> &mut Self {
+        self.finder.url_must_have_scheme(url_must_have_scheme);
+        self.url_must_have_scheme = url_must_have_scheme;
+        self
+    }
+
+    /// Registers a provider. Providers are tried in order of descending priority, the
+    /// built-in providers have priority `0` except [`MASTODON`] with `-1`, and providers
+    /// with equal priority are tried in the order they were registered. A provider with
+    /// the same name as an already registered one replaces it, keeping its place if the
+    /// priority is the same.
+    pub fn register_provider<P: Provider + 'static>(
+        &mut self,
+        provider: P,
+        priority: i32,
+    ) -> &mut Self {
+        let replaced = self
+            .providers
+            .iter()
+            .position(|(registered_priority, registered)| {
+                *registered_priority == priority && registered.name() == provider.name()
+            });
+        if let Some(index) = replaced {
+            self.providers[index] = (priority, Box::new(provider));
+            return self;
         }
 
-        let mut cleaned_links: Vec<(String, String)> = Vec::new();
-        for link in links {
-            let url = match Url::parse(link.as_str()) {
-                Ok(url) => url,
-                Err(_) => continue,
-            };
-
-            let cleaned_url: String;
-            if let Some(youtube_link) = parse_youtube_url(self, &url) {
-                cleaned_url = youtube_link;
-            } else if let Some(twitter_link) = parse_twitter_url(self, &url) {
-                cleaned_url = twitter_link;
-            } else if let Some(instagram_link) = parse_instagram_url(self, &url) {
-                cleaned_url = instagram_link;
-            } else if let Some(spotify_link) = parse_spotify_url(self, &url) {
-                cleaned_url = spotify_link;
-            } else if let Some(substack_link) = parse_substack_url(self, &url) {
-                cleaned_url = substack_link;
-            } else {
-                continue;
-            }
+        self.providers
+            .retain(|(_, registered)| registered.name() != provider.name());
+        let index = self
+            .providers
+            .iter()
+            .position(|(registered_priority, _)| *registered_priority < priority)
+            .unwrap_or(self.providers.len());
+        self.providers.insert(index, (priority, Box::new(provider)));
+        self
+    }
 
-            let diff_to_original: String = diff::chars(&cleaned_url, url.as_str())
-                .into_iter()
-                .filter_map(|result| match result {
-                    diff::Result::Right(r) => Some(r.to_string()),
-                    _ => None,
-                })
-                .collect::<Vec<String>>()
-                .join("");
+    /// Enables or disables a registered provider by name, all providers are enabled by default
+    pub fn enable_provider(&mut self, provider: &str, enabled: bool) -> &mut Self {
+        if enabled {
+            self.disabled_providers.remove(provider);
+        } else {
+            self.disabled_providers.insert(provider.to_string());
+        }
+        self
+    }
+
+    /// Sets how much is stripped from URLs (default [`Strictness::Standard`])
+    pub fn strictness(&mut self, strictness: Strictness) -> &mut Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Overrides the strictness for a single provider
+    pub fn provider_strictness(&mut self, provider: &str, strictness: Strictness) -> &mut Self {
+        self.provider_strictness
+            .insert(provider.to_string(), strictness);
+        self
+    }
+
+    /// The strictness a provider should clean with
+    pub fn strictness_for(&self, provider: &str) -> Strictness {
+        self.provider_strictness
+            .get(provider)
+            .copied()
+            .unwrap_or(self.strictness)
+    }
 
-            cleaned_links.push((cleaned_url, diff_to_original));
+    /// Enables keep-list mode for a provider: only the parameters it knows to be functional
+    /// survive, everything else is removed. Has no effect on providers without a keep list.
+    pub fn keep_list_mode(&mut self, provider: &str, enabled: bool) -> &mut Self {
+        if enabled {
+            self.keep_list_providers.insert(provider.to_string());
+        } else {
+            self.keep_list_providers.remove(provider);
         }
-        if !cleaned_links.is_empty() {
-            Some(cleaned_links)
+        self
+    }
+
+    /// Returns true if keep-list mode is enabled for the provider
+    pub fn keep_list_enabled(&self, provider: &str) -> bool {
+        self.keep_list_providers.contains(provider)
+    }
+
+    /// Sets whether URLs of sites without a provider are analyzed for parameters that look
+    /// like trackers (default [`Heuristics::Off`])
+    pub fn heuristics(&mut self, heuristics: Heuristics) -> &mut Self {
+        self.heuristics = heuristics;
+        self
+    }
+
+    /// Sets whether share links identifying the sharer, like `reddit.com/r/rust/s/<token>`,
+    /// are reported as requiring resolution (default `true`)
+    pub fn report_share_links(&mut self, report_share_links: bool) -> &mut Self {
+        self.report_share_links = report_share_links;
+        self
+    }
+
+    /// Returns true if share links are reported as requiring resolution
+    pub fn reports_share_links(&self) -> bool {
+        self.report_share_links
+    }
+
+    /// Names of the registered providers in the order they are tried
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .map(|(_, provider)| provider.name())
+            .collect()
+    }
+
+    pub fn parse_any_text(&self, input: &str) -> Option<Vec<Report>> {
+        let reports: Vec<Report> = self
+            .finder
+            .links(input)
+            .filter_map(|link| self.clean_link(link.as_str()))
+            .collect();
+
+        if !reports.is_empty() {
+            Some(reports)
         } else {
             None
         }
     }
 
     pub fn sanitize_in_place(&self, input: &mut String) -> Option<()> {
-        let original = input.clone();
-        let parsed_links = self.parse_any_text(&original);
-
-        if let Some(cleaned_links) = parsed_links {
-            let mut finder = LinkFinder::new();
-            finder.kinds(&[LinkKind::Url]);
-            let mut last_end = 0;
-            let mut result = String::new();
-
-            for link in finder.links(&original) {
-                let start = link.start();
-                let end = link.end();
-
-                // Add the text before the link
-                result.push_str(&original[last_end..start]);
-
-                // Find the corresponding cleaned link
-                if let Some(cleaned_link) = cleaned_links.iter().find(|&cl| {
-                    let original_url = Url::parse(link.as_str()).ok();
-                    let cleaned_url = Url::parse(&cl.0).ok();
-                    match (original_url, cleaned_url) {
-                        (Some(ou), Some(cu)) => ou.path() == cu.path() && ou.host() == cu.host(),
-                        _ => false,
-                    }
-                }) {
-                    result.push_str(&cleaned_link.0);
+        let mut last_end = 0;
+        let mut result = String::new();
+        let mut changed = false;
+
+        for link in self.finder.links(input) {
+            // Add the text before the link
+            result.push_str(&input[last_end..link.start()]);
+
+            if let Some(report) = self.clean_link(link.as_str()) {
+                changed |= report.is_changed();
+                result.push_str(&report.cleaned);
+            } else {
+                // If no cleaned version found, keep the original link
+                result.push_str(link.as_str());
+            }
+
+            last_end = link.end();
+        }
+
+        if !changed {
+            return None;
+        }
+
+        // Add any remaining text after the last link
+        result.push_str(&input[last_end..]);
+
+        // Update the input string
+        *input = result;
+
+        Some(())
+    }
+
+    /// Cleans a single link found in the input with the first matching provider
+    fn clean_link(&self, link: &str) -> Option<Report> {
+        let (url, has_scheme) = match Url::parse(link) {
+            Ok(url) => (url, true),
+            Err(_) if !self.url_must_have_scheme => {
+                (Url::parse(&format!("https://{}", link)).ok()?, false)
+            }
+            Err(_) => return None,
+        };
+
+        let (current, findings, site_provider) = self.clean_with_providers(&url)?;
+
+        if findings.is_empty() && current == url {
+            return None;
+        }
+        let provider_name = match site_provider {
+            Some(provider) => provider.name().to_string(),
+            None => findings
+                .first()
+                .map(|finding| finding.provider.clone())
+                .unwrap_or_default(),
+        };
+        let cleaned_url = current.to_string();
+
+        // Only flagged, nothing was removed
+        if cleaned_url == url.as_str() {
+            return Some(Report {
+                original: link.to_string(),
+                cleaned: link.to_string(),
+                removed: String::new(),
+                provider: provider_name,
+                findings,
+            });
+        }
+
+        let diff_to_original: String = diff::chars(&cleaned_url, url.as_str())
+            .into_iter()
+            .filter_map(|result| match result {
+                diff::Result::Right(r) => Some(r.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        // Write schemeless links back the way they were found, without the `/` path
+        // `Url` adds to links that had none
+        let cleaned_url = match cleaned_url.strip_prefix("https://") {
+            Some(schemeless) if !has_scheme => {
+                let had_path = link
+                    .find(['/', '?', '#'])
+                    .is_some_and(|index| link[index..].starts_with('/'));
+                if current.path() == "/" && !had_path {
+                    schemeless.replacen('/', "", 1)
                 } else {
-                    // If no cleaned version found, keep the original link
-                    result.push_str(link.as_str());
+                    schemeless.to_string()
                 }
+            }
+            _ => cleaned_url,
+        };
+
+        Some(Report {
+            original: link.to_string(),
+            cleaned: cleaned_url,
+            removed: diff_to_original,
+            provider: provider_name,
+            findings,
+        })
+    }
+
+    /// Runs the global providers and then the first matching site provider, or the
+    /// heuristics if no site provider matches the URL
+    fn clean_with_providers(
+        &self,
+        url: &Url,
+    ) -> Option<(Url, Vec<Finding>, Option<&dyn Provider>)> {
+        // Global providers first, so site providers and heuristics see what is left
+        let (mut current, mut findings) = self.clean_globally(url)?;
 
-                last_end = end;
+        let site_provider = self
+            .enabled_providers()
+            .filter(|provider| !provider.is_global())
+            .find(|provider| provider.matches(&current));
+        let (provider_name, cleaned) = match site_provider {
+            Some(provider) => (provider.name(), provider.clean(self, &current)),
+            None => ("heuristics", self.clean_heuristically(&current)),
+        };
+        if let Some(cleaned) = cleaned {
+            current = Url::parse(&cleaned.url).ok()?;
+            findings.extend(with_provider(cleaned.findings, provider_name));
+        }
+        Some((current, findings, site_provider))
+    }
+
+    /// Runs the enabled global providers
+    fn clean_globally(&self, url: &Url) -> Option<(Url, Vec<Finding>)> {
+        let mut current = url.clone();
+        let mut findings = Vec::new();
+        for provider in self.enabled_providers().filter(|p| p.is_global()) {
+            if let Some(cleaned) = provider.clean(self, &current) {
+                current = Url::parse(&cleaned.url).ok()?;
+                findings.extend(with_provider(cleaned.findings, provider.name()));
             }
+        }
+        Some((current, findings))
+    }
+
+    /// Cleans the URL with the enabled global providers and then `provider`, like links in
+    /// text are cleaned. Returns `None` if the provider does not match or nothing was removed.
+    pub(crate) fn parse_with(&self, provider: &dyn Provider, url: &Url) -> Option<String> {
+        if !provider.matches(url) {
+            return None;
+        }
+        let (mut current, _) = self.clean_globally(url)?;
+        if let Some(cleaned) = provider.clean(self, &current) {
+            current = Url::parse(&cleaned.url).ok()?;
+        }
+        (current != *url).then(|| current.to_string())
+    }
 
-            // Add any remaining text after the last link
-            result.push_str(&original[last_end..]);
+    /// Cleans a URL nested in another one, like the target of a redirect, with all enabled
+    /// providers. Returns `None` if there was nothing to remove.
+    pub fn clean_nested_url(&self, url: &Url) -> Option<Cleaned> {
+        let (cleaned, findings, _) = self.clean_with_providers(url)?;
+        if findings.is_empty() && cleaned == *url {
+            return None;
+        }
+        Some(Cleaned {
+            url: cleaned.to_string(),
+            findings,
+        })
+    }
 
-            // Update the input string
-            *input = result;
+    fn enabled_providers(&self) -> impl Iterator<Item = &dyn Provider> {
+        self.providers
+            .iter()
+            .map(|(_, provider)| provider.as_ref())
+            .filter(|provider| !self.disabled_providers.contains(provider.name()))
+    }
 
-            Some(())
-        } else {
-            None
+    /// Flags, and depending on the heuristics setting removes, parameters that look like
+    /// trackers
+    fn clean_heuristically(&self, url: &Url) -> Option<Cleaned> {
+        if self.heuristics == Heuristics::Off {
+            return None;
         }
+
+        let mut findings = heuristics::analyze(url);
+        if findings.is_empty() {
+            return None;
+        }
+
+        if self.heuristics == Heuristics::Report {
+            return Some(Cleaned {
+                url: url.to_string(),
+                findings,
+            });
+        }
+
+        let flagged: HashSet<String> = findings.iter().map(|f| f.name.clone()).collect();
+        let cleaned = strip_query_params(url, |key| {
+            flagged.contains(key).then_some(Category::Unknown)
+        })?;
+        for finding in &mut findings {
+            finding.action = Action::Removed;
+        }
+        Some(Cleaned {
+            url: cleaned.url,
+            findings,
+        })
     }
 
+    /// Removes the tracking params from the URL if its host is one of `valid_hosts`
     pub fn parse_url(
         &self,
         parsed_url: &Url,
         valid_hosts: &[&str],
-        tracking_params: &[&str],
+        tracking_params: &[TrackingParam],
     ) -> Option<String> {
-        if !valid_hosts.contains(&parsed_url.host_str().unwrap_or("")) {
+        self.clean_url(parsed_url, valid_hosts, tracking_params, self.strictness)
+            .map(|cleaned| cleaned.url)
+    }
+
+    /// Same as `parse_url`, but only removes params up to the given strictness and also
+    /// returns the removed query parameters
+    pub fn clean_url(
+        &self,
+        parsed_url: &Url,
+        valid_hosts: &[&str],
+        tracking_params: &[TrackingParam],
+        strictness: Strictness,
+    ) -> Option<Cleaned> {
+        if !host_matches(parsed_url.host_str().unwrap_or(""), valid_hosts) {
             return None;
         }
 
-        let mut url = parsed_url.clone();
-        let tracking_params: HashSet<_> = tracking_params.iter().cloned().collect();
-
-        let original_pairs: Vec<(String, String)> = url
-            .query_pairs()
-            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        let tracking_params: Vec<_> = tracking_params
+            .iter()
+            .filter(|param| param.strictness <= strictness)
             .collect();
 
-        // Check if there are any non-empty query parameters that are not tracking params
-        if original_pairs.iter().all(|(_, v)| v.is_empty()) {
+        strip_query_params(parsed_url, |key| {
+            tracking_params
+                .iter()
+                .find(|param| param_matches(param.name, key))
+                .map(|param| param.category)
+        })
+    }
+
+    /// Same as `clean_url`, but removes every query parameter that is not in `keep_params`.
+    /// The categories of removed parameters are looked up in `tracking_params`.
+    pub fn clean_url_keep_only(
+        &self,
+        parsed_url: &Url,
+        valid_hosts: &[&str],
+        keep_params: &[&str],
+        tracking_params: &[TrackingParam],
+    ) -> Option<Cleaned> {
+        if !host_matches(parsed_url.host_str().unwrap_or(""), valid_hosts) {
             return None;
         }
 
-        let filtered_pairs: Vec<(String, String)> = original_pairs
-            .iter()
-            .filter(|(key, _)| !tracking_params.contains(key.as_str()))
-            .cloned()
-            .collect();
+        strip_query_params(parsed_url, |key| {
+            if keep_params.iter().any(|keep| param_matches(keep, key)) {
+                return None;
+            }
+            let category = tracking_params
+                .iter()
+                .find(|param| param_matches(param.name, key))
+                .map_or(Category::Unknown, |param| param.category);
+            Some(category)
+        })
+    }
+}
 
-        if original_pairs.len() == filtered_pairs.len() {
-            return None;
+/// Sets the name of the provider that reported the findings, findings of nested URLs keep
+/// the provider that cleaned them
+fn with_provider(findings: Vec<Finding>, provider: &str) -> Vec<Finding> {
+    findings
+        .into_iter()
+        .map(|mut finding| {
+            if finding.provider.is_empty() {
+                finding.provider = provider.to_string();
+            }
+            finding
+        })
+        .collect()
+}
+
+/// Removes every query parameter for which `classify` returns a category
+fn strip_query_params(
+    parsed_url: &Url,
+    classify: impl Fn(&str) -> Option<Category>,
+) -> Option<Cleaned> {
+    let mut url = parsed_url.clone();
+
+    // Kept parameters are written back as they were, so nested URLs stay encoded
+    let original_pairs: Vec<(&str, String, String)> = parsed_url
+        .query()?
+        .split('&')
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| {
+            let (k, v) = url::form_urlencoded::parse(raw.as_bytes())
+                .next()
+                .unwrap_or_default();
+            (raw, k.into_owned(), v.into_owned())
+        })
+        .collect();
+
+    // Check if there are any non-empty query parameters that are not tracking params
+    if original_pairs.iter().all(|(_, _, v)| v.is_empty()) {
+        return None;
+    }
+
+    let mut removed = Vec::new();
+    let mut filtered_pairs = Vec::new();
+    for (raw, name, value) in original_pairs {
+        match classify(&name) {
+            Some(category) => removed.push(Finding::removed(name, value, category)),
+            None => filtered_pairs.push(raw),
         }
+    }
 
-        url.set_query(None);
+    if removed.is_empty() {
+        return None;
+    }
 
-        if !filtered_pairs.is_empty() {
-            let query_string = filtered_pairs
-                .into_iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<String>>()
-                .join("&");
-            url.set_query(Some(&query_string));
+    url.set_query(None);
+
+    if !filtered_pairs.is_empty() {
+        url.set_query(Some(&filtered_pairs.join("&")));
+    }
+
+    Some(Cleaned {
+        url: url.to_string(),
+        findings: removed,
+    })
+}
+
+/// Returns true if the host is one of the patterns, `*.example.com` matches all subdomains
+/// and `example.*` matches all country domains like `example.de` or `example.co.uk`.
+/// `*` matches every host.
+pub(crate) fn host_matches(host: &str, patterns: &[&str]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_prefix("*.") {
+            _ if *pattern == "*" => true,
+            Some(domain) => host
+                .match_indices('.')
+                .any(|(index, _)| domain_matches(&host[index + 1..], domain)),
+            None => domain_matches(host, pattern),
+        })
+}
+
+fn domain_matches(host: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(name) => host
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .is_some_and(is_country_tld),
+        None => host == pattern,
+    }
+}
+
+/// Top level domains used for country versions of a site: `com`, `de`, `co.uk`, `com.au`
+fn is_country_tld(tld: &str) -> bool {
+    let is_country_code =
+        |code: &str| code.len() == 2 && code.chars().all(|c| c.is_ascii_lowercase());
+    match tld.split_once('.') {
+        Some(("co" | "com", code)) => is_country_code(code),
+        Some(_) => false,
+        None => tld == "com" || is_country_code(tld),
+    }
+}
+
+/// Returns true if the path of the URL starts with the segments of one of the patterns,
+/// e.g. `/share/*` or `/@*/#`. Patterns can be limited to a host, e.g. `lnkd.in/*`.
+pub(crate) fn path_matches(url: &Url, patterns: &[&str]) -> bool {
+    let segments: Vec<&str> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+    patterns.iter().any(|pattern| {
+        let (host, path_pattern) = pattern.split_at(pattern.find('/').unwrap_or(pattern.len()));
+        if !host.is_empty() && url.host_str() != Some(host) {
+            return false;
         }
+        let pattern_segments: Vec<&str> =
+            path_pattern.split('/').filter(|s| !s.is_empty()).collect();
+        pattern_segments.len() <= segments.len()
+            && pattern_segments
+                .iter()
+                .zip(&segments)
+                .all(|(pattern, segment)| segment_matches(pattern, segment))
+    })
+}
+
+/// `*` matches any segment, `#` numeric IDs and `@*` all segments starting with `@`
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    match pattern {
+        "#" => !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()),
+        _ => param_matches(pattern, segment),
+    }
+}
 
-        Some(url.to_string())
+/// Returns true if the query parameter name matches the pattern, `pd_rd_*` matches all
+/// names starting with `pd_rd_`
+pub(crate) fn param_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
     }
 }
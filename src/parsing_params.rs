@@ -1,119 +1,2199 @@
 use super::*;
-use crate::parsing_core::Parser;
+use crate::parsing_core::{host_matches, param_matches, Parser};
+use crate::provider::Category::*;
+use crate::provider::Strictness::*;
+use crate::provider::{
+    Category, Cleaned, Finding, PathRule, Provider, Redirect, Rule, Strictness, TrackingParam,
+};
 
-pub fn parse_twitter_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
-    let valid_hosts = [
-        "www.twitter.com",
-        "twitter.com",
-        "t.co",
-        "x.com",
-        "www.x.com",
-    ];
-    let tracking_params = [
-        "utm_source",
-        "utm_medium",
-        "utm_campaign",
-        "utm_term",
-        "utm_content",
-        "s",
-        "t",
-        "src",
-        "ref_src",
-        "ref_url",
-        "twclid",
-    ];
-    parser.parse_url(parsed_url, &valid_hosts, &tracking_params)
-}
+const TWITTER_HOSTS: &[&str] = &[
+    "www.twitter.com",
+    "twitter.com",
+    "t.co",
+    "x.com",
+    "www.x.com",
+];
 
-pub fn parse_youtube_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
-    let valid_hosts = [
+const TWITTER_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_source", Analytics, Conservative),
+    TrackingParam::new("utm_medium", Analytics, Conservative),
+    TrackingParam::new("utm_campaign", Analytics, Conservative),
+    TrackingParam::new("utm_term", Analytics, Conservative),
+    TrackingParam::new("utm_content", Analytics, Conservative),
+    TrackingParam::new("s", ShareId, Standard),
+    TrackingParam::new("t", ShareId, Standard),
+    TrackingParam::new("src", Referral, Standard),
+    TrackingParam::new("ref_src", Referral, Conservative),
+    TrackingParam::new("ref_url", Referral, Conservative),
+];
+
+/// Intent links only carry the post to compose, `t` and `s` are not share IDs there
+const TWITTER_INTENT_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("ref_src", Referral, Conservative),
+    TrackingParam::new("ref_url", Referral, Conservative),
+    TrackingParam::new("original_referer", Referral, Conservative),
+];
+
+const TWITTER_SEARCH_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("src", Referral, Standard), // e.g. `typed_query`
+    TrackingParam::new("ref_src", Referral, Conservative),
+];
+
+pub const YOUTUBE_RULE: Rule = Rule {
+    name: "youtube",
+    category: ShareId,
+    hosts: &[
         "www.youtube.com",
         "youtube.com",
         "youtu.be",
         "yt.be",
         "m.youtube.com",
         "music.youtube.com",
-    ];
-    let tracking_params = [
-        "utm_source",
-        "utm_medium",
-        "utm_campaign",
-        "utm_term",
-        "utm_content",
-        "feature",
-        "gclid",
-        "fbclid",
-        "si",
-        "pp",
-    ];
-    parser.parse_url(parsed_url, &valid_hosts, &tracking_params)
-}
+        "www.youtube-nocookie.com",
+        "youtube-nocookie.com",
+    ],
+    tracking_params: &[
+        TrackingParam::new("utm_source", Analytics, Conservative),
+        TrackingParam::new("utm_medium", Analytics, Conservative),
+        TrackingParam::new("utm_campaign", Analytics, Conservative),
+        TrackingParam::new("utm_term", Analytics, Conservative),
+        TrackingParam::new("utm_content", Analytics, Conservative),
+        TrackingParam::new("feature", Referral, Standard),
+        TrackingParam::new("si", ShareId, Conservative),
+        TrackingParam::new("pp", ShareId, Conservative),
+        TrackingParam::new("ab_channel", Functional, Aggressive),
+    ],
+    keep_params: Some(&["v", "t", "start", "list", "index", "search_query"]),
+    share_paths: &[],
+};
 
-pub fn parse_substack_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
-    let tracking_params = [
-        "utm_source",
-        "utm_medium",
-        "utm_campaign",
-        "utm_term",
-        "utm_content",
-        "source",
-        "r", // referral parameter
-        "s", // subscriber parameter
-    ];
-
-    let host = parsed_url.host_str().unwrap_or("");
-
-    if host == "www.substack.com" || host == "substack.com" || host.ends_with(".substack.com") {
-        parser.parse_url(parsed_url, &[host], &tracking_params)
-    } else {
-        None
-    }
-}
+pub const SUBSTACK: Rule = Rule {
+    name: "substack",
+    category: Referral,
+    hosts: &["substack.com", "*.substack.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_source", Analytics, Conservative),
+        TrackingParam::new("utm_medium", Analytics, Conservative),
+        TrackingParam::new("utm_campaign", Analytics, Conservative),
+        TrackingParam::new("utm_term", Analytics, Conservative),
+        TrackingParam::new("utm_content", Analytics, Conservative),
+        TrackingParam::new("source", Referral, Standard),
+        TrackingParam::new("r", Referral, Standard), // referral parameter
+        TrackingParam::new("s", ShareId, Standard),  // subscriber parameter
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
 
-pub fn parse_spotify_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
-    let valid_hosts = [
+pub const SPOTIFY: Rule = Rule {
+    name: "spotify",
+    category: ShareId,
+    hosts: &[
         "open.spotify.com",
         "play.spotify.com",
         "spotify.com",
         "www.spotify.com",
         "artist.spotify.com",
         "embed.spotify.com",
+    ],
+    tracking_params: &[
+        TrackingParam::new("si", ShareId, Conservative), // Spotify Identifier
+        TrackingParam::new("utm_source", Analytics, Conservative),
+        TrackingParam::new("utm_medium", Analytics, Conservative),
+        TrackingParam::new("utm_campaign", Analytics, Conservative),
+        TrackingParam::new("utm_term", Analytics, Conservative),
+        TrackingParam::new("utm_content", Analytics, Conservative),
+        TrackingParam::new("feature", Referral, Standard),
+        TrackingParam::new("nd", Functional, Standard), // No Delay
+        TrackingParam::new("context", Functional, Standard),
+        TrackingParam::new("context_id", Functional, Standard),
+        TrackingParam::new("sp_cid", Analytics, Conservative), // Spotify Campaign ID
+        TrackingParam::new("sp_ac", Analytics, Conservative),  // Spotify Ad Click
+        TrackingParam::new("sp_gaid", Analytics, Conservative), // Google Advertising ID
+        TrackingParam::new("sp_aid", Analytics, Conservative), // Apple Identifier for Advertisers
+        TrackingParam::new("go", Referral, Standard),          // Generic Origin
+        TrackingParam::new("product", Referral, Standard),
+        TrackingParam::new("referral", Referral, Standard),
+        TrackingParam::new("dl_branch", Referral, Aggressive),
+    ],
+    keep_params: Some(&["highlight", "t"]),
+    share_paths: &[],
+};
+
+pub const INSTAGRAM_RULE: Rule = Rule {
+    name: "instagram",
+    category: ShareId,
+    hosts: &["www.instagram.com", "instagram.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_source", Analytics, Conservative),
+        TrackingParam::new("utm_medium", Analytics, Conservative),
+        TrackingParam::new("utm_campaign", Analytics, Conservative),
+        TrackingParam::new("utm_term", Analytics, Conservative),
+        TrackingParam::new("utm_content", Analytics, Conservative),
+        TrackingParam::new("igshid", ShareId, Conservative),
+        TrackingParam::new("igsh", ShareId, Conservative),
+        TrackingParam::new("_ga", Analytics, Conservative),
+        TrackingParam::new("_gid", Analytics, Conservative),
+        TrackingParam::new("hl", Functional, Aggressive),
+    ],
+    keep_params: Some(&["img_index"]),
+    share_paths: &["/share/*"],
+};
+
+pub const APPLE_MUSIC: Rule = Rule {
+    name: "apple_music",
+    category: ShareId,
+    hosts: &["music.apple.com", "geo.music.apple.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("uo", Referral, Conservative), // Origin of the link
+        TrackingParam::new("app", Referral, Standard),
+        TrackingParam::new("ls", Referral, Conservative),
+        TrackingParam::new("at", Affiliate, Conservative), // Affiliate token
+        TrackingParam::new("ct", Analytics, Conservative), // Campaign token
+        TrackingParam::new("itsct", Analytics, Conservative),
+        TrackingParam::new("itscg", Analytics, Conservative),
+    ],
+    keep_params: Some(&["i", "l"]),
+    share_paths: &[],
+};
+
+pub const DEEZER: Rule = Rule {
+    name: "deezer",
+    category: ShareId,
+    hosts: &["deezer.com", "*.deezer.com", "deezer.page.link"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("deferredFl", Referral, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &["link.deezer.com/*", "deezer.page.link/*"],
+};
+
+pub const TIDAL: Rule = Rule {
+    name: "tidal",
+    category: ShareId,
+    hosts: &["tidal.com", "*.tidal.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("u", ShareId, Conservative),
+    ],
+    keep_params: Some(&[]),
+    share_paths: &[],
+};
+
+pub const SOUNDCLOUD: Rule = Rule {
+    name: "soundcloud",
+    category: ShareId,
+    hosts: &["soundcloud.com", "*.soundcloud.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("si", ShareId, Conservative),
+        TrackingParam::new("ref", Referral, Standard),
+        TrackingParam::new("in", Functional, Standard), // Playlist the track was shared from
+    ],
+    keep_params: None,
+    share_paths: &["on.soundcloud.com/*"],
+};
+
+pub const TWITCH: Rule = Rule {
+    name: "twitch",
+    category: ShareId,
+    hosts: &["twitch.tv", "*.twitch.tv"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("tt_medium", Referral, Conservative),
+        TrackingParam::new("tt_content", Referral, Conservative),
+        TrackingParam::new("sr", Referral, Standard),
+    ],
+    keep_params: Some(&["t"]),
+    share_paths: &[],
+};
+
+pub const VIMEO: Rule = Rule {
+    name: "vimeo",
+    category: ShareId,
+    hosts: &["vimeo.com", "*.vimeo.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("share", ShareId, Conservative),
+        TrackingParam::new("fl", Referral, Standard),
+        TrackingParam::new("fe", Referral, Standard),
+        TrackingParam::new("source", Referral, Standard),
+        TrackingParam::new("owner", Referral, Standard),
+        TrackingParam::new("embedded", Referral, Standard),
+    ],
+    keep_params: Some(&["t", "h"]),
+    share_paths: &[],
+};
+
+pub const DAILYMOTION: Rule = Rule {
+    name: "dailymotion",
+    category: ShareId,
+    hosts: &["dailymotion.com", "*.dailymotion.com", "dai.ly"],
+    tracking_params: &[TrackingParam::new("utm_*", Analytics, Conservative)],
+    keep_params: Some(&["playlist", "start"]),
+    share_paths: &[],
+};
+
+pub const RUMBLE: Rule = Rule {
+    name: "rumble",
+    category: ShareId,
+    hosts: &["rumble.com", "*.rumble.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("e9s", Referral, Conservative),
+        TrackingParam::new("mref", Referral, Standard),
+        TrackingParam::new("mc", Referral, Standard),
+    ],
+    keep_params: Some(&["start"]),
+    share_paths: &[],
+};
+
+pub const BILIBILI: Rule = Rule {
+    name: "bilibili",
+    category: ShareId,
+    hosts: &["bilibili.com", "*.bilibili.com", "b23.tv"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("spm_id_from", Referral, Conservative),
+        TrackingParam::new("from_spmid", Referral, Conservative),
+        TrackingParam::new("vd_source", ShareId, Conservative),
+        TrackingParam::new("share_source", Referral, Conservative),
+        TrackingParam::new("share_medium", Referral, Conservative),
+        TrackingParam::new("share_plat", Referral, Conservative),
+        TrackingParam::new("share_session_id", ShareId, Conservative),
+        TrackingParam::new("share_tag", Referral, Conservative),
+        TrackingParam::new("share_from", Referral, Conservative),
+        TrackingParam::new("bbid", ShareId, Conservative),
+        TrackingParam::new("ts", Analytics, Conservative), // Time of sharing, not the video time
+        TrackingParam::new("unique_k", ShareId, Conservative),
+    ],
+    // `t` is the video timestamp and `p` the part of a multi-part video
+    keep_params: Some(&["t", "p"]),
+    share_paths: &["b23.tv/*"],
+};
+
+const AMAZON_HOSTS: &[&str] = &[
+    "amazon.com",
+    "*.amazon.com",
+    "amazon.ca",
+    "*.amazon.ca",
+    "amazon.com.mx",
+    "*.amazon.com.mx",
+    "amazon.com.br",
+    "*.amazon.com.br",
+    "amazon.co.uk",
+    "*.amazon.co.uk",
+    "amazon.de",
+    "*.amazon.de",
+    "amazon.fr",
+    "*.amazon.fr",
+    "amazon.it",
+    "*.amazon.it",
+    "amazon.es",
+    "*.amazon.es",
+    "amazon.nl",
+    "*.amazon.nl",
+    "amazon.se",
+    "*.amazon.se",
+    "amazon.pl",
+    "*.amazon.pl",
+    "amazon.com.be",
+    "*.amazon.com.be",
+    "amazon.com.tr",
+    "*.amazon.com.tr",
+    "amazon.ae",
+    "*.amazon.ae",
+    "amazon.sa",
+    "*.amazon.sa",
+    "amazon.eg",
+    "*.amazon.eg",
+    "amazon.in",
+    "*.amazon.in",
+    "amazon.co.jp",
+    "*.amazon.co.jp",
+    "amazon.sg",
+    "*.amazon.sg",
+    "amazon.com.au",
+    "*.amazon.com.au",
+    "amazon.cn",
+    "*.amazon.cn",
+    "amzn.to",
+    "amzn.eu",
+    "amzn.asia",
+    "a.co",
+];
+
+const AMAZON_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("ref", Referral, Conservative),
+    TrackingParam::new("ref_", Referral, Conservative),
+    TrackingParam::new("pd_rd_*", Analytics, Conservative),
+    TrackingParam::new("pf_rd_*", Analytics, Conservative),
+    TrackingParam::new("qid", Analytics, Conservative), // Query timestamp
+    TrackingParam::new("sr", Analytics, Conservative),  // Search result rank
+    TrackingParam::new("crid", Analytics, Conservative),
+    TrackingParam::new("sprefix", Analytics, Conservative),
+    TrackingParam::new("keywords", Referral, Standard),
+    TrackingParam::new("content-id", Analytics, Conservative),
+    TrackingParam::new("dib", Analytics, Conservative),
+    TrackingParam::new("dib_tag", Analytics, Conservative),
+    TrackingParam::new("sp_csd", Analytics, Conservative),
+    TrackingParam::new("spLa", Analytics, Conservative),
+    TrackingParam::new("social_share", ShareId, Conservative),
+    TrackingParam::new("_encoding", Functional, Standard),
+    TrackingParam::new("th", Functional, Aggressive), // Selected variant
+    TrackingParam::new("psc", Functional, Aggressive), // Selected variant
+];
+
+/// Parameters crediting the sharer of a link for purchases
+const AMAZON_AFFILIATE_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("tag", Affiliate, Conservative),
+    TrackingParam::new("ascsubtag", Affiliate, Conservative),
+    TrackingParam::new("linkCode", Affiliate, Conservative),
+    TrackingParam::new("linkId", Affiliate, Conservative),
+    TrackingParam::new("camp", Affiliate, Conservative),
+    TrackingParam::new("creative", Affiliate, Conservative),
+    TrackingParam::new("creativeASIN", Affiliate, Conservative),
+];
+
+pub const EBAY: Shop = Shop {
+    rule: Rule {
+        name: "ebay",
+        category: Affiliate,
+        hosts: &["ebay.*", "*.ebay.*"],
+        tracking_params: &[
+            TrackingParam::new("utm_*", Analytics, Conservative),
+            TrackingParam::new("_trkparms", Analytics, Conservative),
+            TrackingParam::new("_trksid", Analytics, Conservative),
+            TrackingParam::new("hash", Analytics, Conservative),
+            TrackingParam::new("amdata", Analytics, Conservative),
+            TrackingParam::new("mkevt", Analytics, Conservative),
+            TrackingParam::new("mkcid", Analytics, Conservative),
+            TrackingParam::new("mkrid", Analytics, Conservative),
+            TrackingParam::new("campid", Affiliate, Conservative),
+            TrackingParam::new("customid", Affiliate, Conservative),
+            TrackingParam::new("toolid", Affiliate, Conservative),
+            TrackingParam::new("_from", Referral, Standard),
+        ],
+        keep_params: None,
+        share_paths: &[],
+    },
+    item_segment: "itm",
+};
+
+pub const ALIEXPRESS: Rule = Rule {
+    name: "aliexpress",
+    category: Affiliate,
+    hosts: &["aliexpress.*", "*.aliexpress.*"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("spm", Analytics, Conservative),
+        TrackingParam::new("scm", Analytics, Conservative),
+        TrackingParam::new("algo_pvid", Analytics, Conservative),
+        TrackingParam::new("algo_expid", Analytics, Conservative),
+        TrackingParam::new("aff_*", Affiliate, Conservative),
+        TrackingParam::new("sk", Affiliate, Conservative),
+        TrackingParam::new("pdp_npi", Analytics, Conservative),
+        TrackingParam::new("pdp_ext_f", Analytics, Conservative),
+        TrackingParam::new("gatewayAdapt", Functional, Aggressive),
+    ],
+    keep_params: None,
+    share_paths: &["a.aliexpress.com/*"],
+};
+
+pub const ETSY: Shop = Shop {
+    rule: Rule {
+        name: "etsy",
+        category: Affiliate,
+        hosts: &["etsy.com", "*.etsy.com"],
+        tracking_params: &[
+            TrackingParam::new("utm_*", Analytics, Conservative),
+            TrackingParam::new("click_key", Analytics, Conservative),
+            TrackingParam::new("click_sum", Analytics, Conservative),
+            TrackingParam::new("ref", Referral, Conservative),
+            TrackingParam::new("pro", Analytics, Conservative),
+            TrackingParam::new("frs", Analytics, Conservative),
+            TrackingParam::new("sts", Analytics, Conservative),
+            TrackingParam::new("ga_*", Analytics, Conservative),
+            TrackingParam::new("organic_search_click", Analytics, Conservative),
+            TrackingParam::new("plkey", Analytics, Conservative),
+        ],
+        keep_params: None,
+        share_paths: &[],
+    },
+    item_segment: "listing",
+};
+
+pub const WALMART: Shop = Shop {
+    rule: Rule {
+        name: "walmart",
+        category: Affiliate,
+        hosts: &["walmart.com", "*.walmart.com", "walmart.ca", "*.walmart.ca"],
+        tracking_params: &[
+            TrackingParam::new("utm_*", Analytics, Conservative),
+            TrackingParam::new("ath*", Analytics, Conservative), // athbdg, athcpid, athznid, ...
+            TrackingParam::new("wmlspartner", Affiliate, Conservative),
+            TrackingParam::new("affiliates_ad_id", Affiliate, Conservative),
+            TrackingParam::new("sourceid", Affiliate, Conservative),
+            TrackingParam::new("veh", Affiliate, Conservative),
+            TrackingParam::new("from", Referral, Standard),
+            TrackingParam::new("classType", Functional, Aggressive),
+        ],
+        keep_params: None,
+        share_paths: &[],
+    },
+    item_segment: "ip",
+};
+
+pub const TEMU: Rule = Rule {
+    name: "temu",
+    category: Affiliate,
+    hosts: &["temu.com", "*.temu.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("_x_*", Analytics, Conservative),
+        TrackingParam::new("refer_page_*", Referral, Conservative),
+        TrackingParam::new("_bg_fs", Analytics, Conservative),
+        TrackingParam::new("_p_rfs", Analytics, Conservative),
+        TrackingParam::new("share_uin", ShareId, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &["share.temu.com/*"],
+};
+
+pub const FACEBOOK: Rule = Rule {
+    name: "facebook",
+    category: ShareId,
+    hosts: &[
+        "facebook.com",
+        "*.facebook.com",
+        "fb.com",
+        "*.fb.com",
+        "fb.watch",
+        "messenger.com",
+        "*.messenger.com",
+        "threads.net",
+        "*.threads.net",
+        "threads.com",
+        "*.threads.com",
+    ],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("__cft__*", Analytics, Conservative), // Click feedback token
+        TrackingParam::new("__tn__", Analytics, Conservative),   // Tracking node
+        TrackingParam::new("__xts__*", Analytics, Conservative),
+        TrackingParam::new("mibextid", ShareId, Conservative), // Mobile in-browser extension ID
+        TrackingParam::new("rdid", ShareId, Conservative),     // Redirect ID
+        TrackingParam::new("share_url", ShareId, Conservative),
+        TrackingParam::new("igshid", ShareId, Conservative),
+        TrackingParam::new("igsh", ShareId, Conservative),
+        TrackingParam::new("xmt", ShareId, Conservative),
+        TrackingParam::new("slof", ShareId, Standard),
+        TrackingParam::new("hc_ref", Referral, Conservative),
+        TrackingParam::new("ref", Referral, Standard),
+        TrackingParam::new("refsrc", Referral, Standard),
+        TrackingParam::new("sfnsn", Referral, Standard),
+    ],
+    keep_params: None,
+    share_paths: &["/share/*"],
+};
+
+pub const TIKTOK_RULE: Rule = Rule {
+    name: "tiktok",
+    category: ShareId,
+    hosts: &["tiktok.com", "*.tiktok.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("_r", ShareId, Conservative),
+        TrackingParam::new("_t", ShareId, Conservative),
+        TrackingParam::new("_d", ShareId, Conservative),
+        TrackingParam::new("is_from_webapp", Referral, Conservative),
+        TrackingParam::new("is_copy_url", Referral, Conservative),
+        TrackingParam::new("sender_device", ShareId, Conservative),
+        TrackingParam::new("sender_web_id", ShareId, Conservative),
+        TrackingParam::new("web_id", ShareId, Conservative),
+        TrackingParam::new("u_code", ShareId, Conservative), // Sharing user
+        TrackingParam::new("user_id", ShareId, Conservative),
+        TrackingParam::new("sec_user_id", ShareId, Conservative),
+        TrackingParam::new("share_app_id", ShareId, Conservative),
+        TrackingParam::new("share_item_id", ShareId, Conservative),
+        TrackingParam::new("share_link_id", ShareId, Conservative),
+        TrackingParam::new("social_share_type", Referral, Conservative),
+        TrackingParam::new("checksum", ShareId, Conservative),
+        TrackingParam::new("timestamp", ShareId, Conservative),
+        TrackingParam::new("tt_from", Referral, Conservative),
+        TrackingParam::new("enter_from", Referral, Standard),
+        TrackingParam::new("enter_method", Referral, Standard),
+        TrackingParam::new("refer", Referral, Standard),
+        TrackingParam::new("source", Referral, Standard),
+        TrackingParam::new("preview_pb", Analytics, Standard),
+        TrackingParam::new("lang", Functional, Aggressive),
+    ],
+    keep_params: None,
+    share_paths: &["/t/*", "vm.tiktok.com/*", "vt.tiktok.com/*"],
+};
+
+pub const LINKEDIN: Rule = Rule {
+    name: "linkedin",
+    category: ShareId,
+    hosts: &["linkedin.com", "*.linkedin.com", "lnkd.in"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("trk", Referral, Conservative),
+        TrackingParam::new("trkInfo", Referral, Conservative),
+        TrackingParam::new("trkEmail", Analytics, Conservative),
+        TrackingParam::new("trackingId", Analytics, Conservative),
+        TrackingParam::new("refId", Analytics, Conservative),
+        TrackingParam::new("lipi", Analytics, Conservative), // Page instance
+        TrackingParam::new("licu", Analytics, Conservative), // Control URN
+        TrackingParam::new("midToken", ShareId, Conservative), // Email member token
+        TrackingParam::new("midSig", ShareId, Conservative),
+        TrackingParam::new("otpToken", ShareId, Conservative), // One-time login token
+        TrackingParam::new("eid", ShareId, Conservative),
+        TrackingParam::new("eBP", Analytics, Conservative),
+        TrackingParam::new("rcm", Referral, Standard),
+        TrackingParam::new("src", Referral, Standard),
+        TrackingParam::new("veh", Referral, Standard),
+        TrackingParam::new("origin", Referral, Standard),
+        TrackingParam::new("originalSubdomain", Referral, Standard),
+    ],
+    keep_params: Some(&[
+        "currentJobId",
+        "keywords",
+        "geoId",
+        "location",
+        "distance",
+        "start",
+        "sortBy",
+        "f_*",
+    ]),
+    // lnkd.in short links are created per share
+    share_paths: &["lnkd.in/*"],
+};
+
+pub const REDDIT: Rule = Rule {
+    name: "reddit",
+    category: ShareId,
+    hosts: &["reddit.com", "*.reddit.com", "redd.it", "*.redd.it"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("share_id", ShareId, Conservative),
+        TrackingParam::new("rdt", ShareId, Conservative),
+        TrackingParam::new("correlation_id", ShareId, Conservative),
+        TrackingParam::new("$deep_link", Referral, Conservative),
+        TrackingParam::new("$3p", Referral, Conservative),
+        TrackingParam::new("_branch_match_id", Analytics, Conservative),
+        TrackingParam::new("_branch_referrer", Referral, Conservative),
+        TrackingParam::new("ref_source", Referral, Conservative),
+        TrackingParam::new("ref_campaign", Referral, Conservative),
+        TrackingParam::new("ref", Referral, Standard),
+        TrackingParam::new("post_fullname", Referral, Standard),
+        TrackingParam::new("post_index", Referral, Standard),
+        TrackingParam::new("context", Functional, Standard), // Number of parent comments
+    ],
+    keep_params: None,
+    // Share links created by the app identify the sharer
+    share_paths: &["/r/*/s/*"],
+};
+
+pub const MEDIUM_RULE: Rule = Rule {
+    name: "medium",
+    category: Referral,
+    hosts: &["medium.com", "*.medium.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("source", Referral, Conservative),
+        TrackingParam::new("sk", ShareId, Conservative), // Friend link key of the sharer
+    ],
+    keep_params: Some(&[]),
+    share_paths: &[],
+};
+
+pub const NYTIMES: Rule = Rule {
+    name: "nytimes",
+    category: Referral,
+    hosts: &["nytimes.com", "*.nytimes.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("smid", Referral, Conservative),
+        TrackingParam::new("smtyp", Referral, Conservative),
+        TrackingParam::new("sgrp", Referral, Conservative),
+        TrackingParam::new("referringSource", Referral, Standard),
+        // Gift article token, removing it puts the article behind the paywall again
+        TrackingParam::new("unlocked_article_code", ShareId, Aggressive),
+    ],
+    keep_params: Some(&["unlocked_article_code"]),
+    share_paths: &[],
+};
+
+pub const BLOOMBERG: Rule = Rule {
+    name: "bloomberg",
+    category: Referral,
+    hosts: &["bloomberg.com", "*.bloomberg.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("srnd", Referral, Conservative),
+        TrackingParam::new("cmpid", Analytics, Conservative),
+        TrackingParam::new("leadSource", Referral, Conservative),
+        TrackingParam::new("sref", ShareId, Conservative),
+    ],
+    keep_params: Some(&[]),
+    share_paths: &[],
+};
+
+pub const GUARDIAN: Rule = Rule {
+    name: "guardian",
+    category: Referral,
+    hosts: &["theguardian.com", "*.theguardian.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("CMP", Referral, Conservative),
+    ],
+    keep_params: Some(&["page", "filterKeyEvents"]),
+    share_paths: &[],
+};
+
+pub const BBC: Rule = Rule {
+    name: "bbc",
+    category: Referral,
+    hosts: &["bbc.com", "*.bbc.com", "bbc.co.uk", "*.bbc.co.uk"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("at_*", Analytics, Conservative),
+        TrackingParam::new("ocid", Referral, Conservative),
+    ],
+    keep_params: Some(&["page"]),
+    share_paths: &[],
+};
+
+pub const PINTEREST: Rule = Rule {
+    name: "pinterest",
+    category: ShareId,
+    hosts: &["pinterest.*", "*.pinterest.*", "pin.it"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("invite_code", ShareId, Conservative),
+        TrackingParam::new("sender", ShareId, Conservative),
+        TrackingParam::new("sfo", Referral, Conservative),
+        TrackingParam::new("mt", Referral, Standard),
+    ],
+    keep_params: None,
+    share_paths: &["pin.it/*"],
+};
+
+pub const SNAPCHAT: Rule = Rule {
+    name: "snapchat",
+    category: ShareId,
+    hosts: &["snapchat.com", "*.snapchat.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("share_id", ShareId, Conservative),
+        TrackingParam::new("sid", ShareId, Conservative),
+        TrackingParam::new("locale", Functional, Standard),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const TUMBLR: Rule = Rule {
+    name: "tumblr",
+    category: ShareId,
+    hosts: &["tumblr.com", "*.tumblr.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("source", Referral, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const BLUESKY: Rule = Rule {
+    name: "bluesky",
+    category: ShareId,
+    hosts: &["bsky.app", "*.bsky.app"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("ref_src", Referral, Conservative),
+    ],
+    keep_params: Some(&["q"]),
+    share_paths: &[],
+};
+
+/// Posts on any Mastodon instance. Registered at priority `-1`, so it is tried after the
+/// providers matching by host, including custom ones registered at the default priority.
+pub const MASTODON: PathRule = PathRule {
+    rule: Rule {
+        name: "mastodon",
+        category: ShareId,
+        hosts: &["*"],
+        tracking_params: &[TrackingParam::new("utm_*", Analytics, Conservative)],
+        keep_params: Some(&[]),
+        share_paths: &[],
+    },
+    paths: &["/@*/#"],
+};
+
+pub const BOOKING: Rule = Rule {
+    name: "booking",
+    category: Affiliate,
+    hosts: &["booking.com", "*.booking.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        // Partner and campaign IDs, these can change the displayed prices
+        TrackingParam::new("aid", Affiliate, Standard),
+        TrackingParam::new("label", Affiliate, Standard),
+        TrackingParam::new("sid", ShareId, Conservative), // Session ID
+        TrackingParam::new("srpvid", Analytics, Conservative),
+        TrackingParam::new("ucfs", Analytics, Conservative),
+        TrackingParam::new("srepoch", Analytics, Conservative),
+        TrackingParam::new("hpos", Analytics, Standard),
+        TrackingParam::new("hapos", Analytics, Standard),
+        TrackingParam::new("all_sr_blocks", Functional, Aggressive),
+        TrackingParam::new("highlighted_blocks", Functional, Aggressive),
+    ],
+    keep_params: Some(&[
+        "checkin",
+        "checkout",
+        "group_adults",
+        "group_children",
+        "no_rooms",
+        "req_adults",
+        "req_children",
+        "age",
+        "selected_currency",
+        "lang",
+    ]),
+    share_paths: &[],
+};
+
+pub const AIRBNB: Rule = Rule {
+    name: "airbnb",
+    category: Affiliate,
+    hosts: &["airbnb.*", "*.airbnb.*"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("source_impression_id", Analytics, Conservative),
+        TrackingParam::new("federated_search_id", Analytics, Conservative),
+        TrackingParam::new("s", Referral, Conservative),
+        TrackingParam::new("unique_share_id", ShareId, Conservative),
+        TrackingParam::new("viralityEntryPoint", Referral, Conservative),
+        TrackingParam::new("previous_page_section_name", Referral, Standard),
+    ],
+    keep_params: Some(&[
+        "check_in",
+        "check_out",
+        "adults",
+        "children",
+        "infants",
+        "pets",
+        "guests",
+    ]),
+    share_paths: &[],
+};
+
+pub const EXPEDIA: Rule = Rule {
+    name: "expedia",
+    category: Affiliate,
+    hosts: &["expedia.*", "*.expedia.*"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("pwaLob", Referral, Conservative),
+        TrackingParam::new("rfrr", Referral, Conservative),
+        TrackingParam::new("semdtl", Analytics, Conservative),
+    ],
+    keep_params: Some(&[
+        "chkin",
+        "chkout",
+        "startDate",
+        "endDate",
+        "d1",
+        "d2",
+        "rm1",
+        "adults",
+        "children",
+        "regionId",
+        "destination",
+    ]),
+    share_paths: &[],
+};
+
+pub const SKYSCANNER: Rule = Rule {
+    name: "skyscanner",
+    category: Affiliate,
+    hosts: &[
+        "skyscanner.*",
+        "*.skyscanner.*",
+        "skyscanner.net",
+        "*.skyscanner.net",
+    ],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("associateid", Affiliate, Standard),
+        TrackingParam::new("previousCultureSource", Referral, Standard),
+        TrackingParam::new("redirectedFrom", Referral, Standard),
+    ],
+    keep_params: Some(&[
+        "adults",
+        "adultsv2",
+        "children",
+        "childrenv2",
+        "cabinclass",
+        "rtn",
+        "preferdirects",
+        "outboundaltsenabled",
+        "inboundaltsenabled",
+    ]),
+    share_paths: &[],
+};
+
+pub const STEAM_LINKFILTER: Redirect = Redirect {
+    name: "steam_linkfilter",
+    hosts: &["steamcommunity.com"],
+    paths: &["/linkfilter"],
+    param: "u",
+};
+
+pub const STEAM: Rule = Rule {
+    name: "steam",
+    category: Referral,
+    hosts: &[
+        "steampowered.com",
+        "*.steampowered.com",
+        "steamcommunity.com",
+    ],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("snr", Referral, Conservative), // Page and widget the link was on
+        TrackingParam::new("curator_clanid", Affiliate, Standard),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const APP_STORE: Rule = Rule {
+    name: "app_store",
+    category: Affiliate,
+    hosts: &["apps.apple.com", "itunes.apple.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("pt", Affiliate, Conservative), // Provider token
+        TrackingParam::new("ct", Analytics, Conservative), // Campaign token
+        TrackingParam::new("mt", Referral, Conservative),  // Media type of the link
+        TrackingParam::new("uo", Referral, Conservative),
+        TrackingParam::new("at", Affiliate, Conservative),
+        TrackingParam::new("ls", Referral, Conservative),
+        TrackingParam::new("itsct", Analytics, Conservative),
+        TrackingParam::new("itscg", Analytics, Conservative),
+    ],
+    keep_params: Some(&["l", "platform", "see-all"]),
+    share_paths: &[],
+};
+
+pub const ITCH_IO: Rule = Rule {
+    name: "itch_io",
+    category: Referral,
+    hosts: &["itch.io", "*.itch.io"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("ac", Analytics, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const SLACK_REDIRECT: Redirect = Redirect {
+    name: "slack",
+    hosts: &["slack-redir.net"],
+    paths: &["/link"],
+    param: "url",
+};
+
+pub const DISCORD: Rule = Rule {
+    name: "discord",
+    category: ShareId,
+    hosts: &[
+        "discord.com",
+        "*.discord.com",
+        "discord.gg",
+        "discordapp.com",
+        "*.discordapp.com",
+    ],
+    tracking_params: &[TrackingParam::new("utm_*", Analytics, Conservative)],
+    keep_params: Some(&["event"]),
+    share_paths: &["discord.com/ra/*"],
+};
+
+pub const WHATSAPP: Rule = Rule {
+    name: "whatsapp",
+    category: ShareId,
+    hosts: &["wa.me", "whatsapp.com", "*.whatsapp.com"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("source", Referral, Standard), // Ad that opened the chat
+        TrackingParam::new("data", Analytics, Standard),
+    ],
+    keep_params: Some(&["phone", "text", "type", "app_absent"]),
+    share_paths: &[],
+};
+
+pub const TELEGRAM: Rule = Rule {
+    name: "telegram",
+    category: ShareId,
+    hosts: &["t.me", "telegram.me", "telegram.dog"],
+    tracking_params: &[
+        TrackingParam::new("utm_*", Analytics, Conservative),
+        TrackingParam::new("ref", Referral, Conservative),
+        // Bot deep link payloads, mostly referral codes
+        TrackingParam::new("start", Referral, Aggressive),
+        TrackingParam::new("startapp", Referral, Aggressive),
+    ],
+    keep_params: Some(&[
+        "start",
+        "startapp",
+        "startgroup",
+        "single",
+        "comment",
+        "thread",
+    ]),
+    share_paths: &[],
+};
+
+/// Email marketing platforms add their parameters to links to any site, so these rules are
+/// global. Each platform can be disabled on its own.
+pub const MAILCHIMP: Rule = Rule {
+    name: "mailchimp",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("mc_cid", Analytics, Conservative), // Campaign ID
+        TrackingParam::new("mc_eid", ShareId, Conservative),   // Subscriber ID
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const HUBSPOT: Rule = Rule {
+    name: "hubspot",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("_hsenc", ShareId, Conservative),
+        TrackingParam::new("_hsmi", Analytics, Conservative),
+        TrackingParam::new("__hssc", Analytics, Conservative),
+        TrackingParam::new("__hstc", ShareId, Conservative),
+        TrackingParam::new("__hsfp", ShareId, Conservative), // Browser fingerprint
+        TrackingParam::new("hsCtaTracking", Analytics, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const MARKETO: Rule = Rule {
+    name: "marketo",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("mkt_tok", ShareId, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const KLAVIYO: Rule = Rule {
+    name: "klaviyo",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("_kx", ShareId, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const VERO: Rule = Rule {
+    name: "vero",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("vero_id", ShareId, Conservative),
+        TrackingParam::new("vero_conv", Analytics, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const OMEDA: Rule = Rule {
+    name: "omeda",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("oly_enc_id", ShareId, Conservative),
+        TrackingParam::new("oly_anon_id", ShareId, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const WICKED_REPORTS: Rule = Rule {
+    name: "wicked_reports",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("wickedid", ShareId, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const SHARPSPRING: Rule = Rule {
+    name: "sharpspring",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("ss_source", Analytics, Conservative),
+        TrackingParam::new("ss_campaign_id", Analytics, Conservative),
+        TrackingParam::new("ss_campaign_name", Analytics, Conservative),
+        TrackingParam::new("ss_campaign_sent_date", Analytics, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const EMAIL_MARKETING: &[Rule] = &[
+    MAILCHIMP,
+    HUBSPOT,
+    MARKETO,
+    KLAVIYO,
+    VERO,
+    OMEDA,
+    WICKED_REPORTS,
+    SHARPSPRING,
+];
+
+/// Click identifiers ad networks add to the landing pages of ads, these rules are global
+/// and each network can be disabled on its own
+pub const GOOGLE_ADS: Rule = Rule {
+    name: "google_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("gclid", Analytics, Conservative),
+        TrackingParam::new("gclsrc", Analytics, Conservative),
+        TrackingParam::new("gbraid", Analytics, Conservative), // iOS app to web
+        TrackingParam::new("wbraid", Analytics, Conservative), // iOS web to app
+        TrackingParam::new("dclid", Analytics, Conservative),  // Display & Video 360
+        TrackingParam::new("gad_source", Analytics, Conservative),
+        TrackingParam::new("gad_campaignid", Analytics, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const MICROSOFT_ADS: Rule = Rule {
+    name: "microsoft_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("msclkid", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const META_ADS: Rule = Rule {
+    name: "meta_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("fbclid", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const TIKTOK_ADS: Rule = Rule {
+    name: "tiktok_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("ttclid", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const TWITTER_ADS: Rule = Rule {
+    name: "twitter_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("twclid", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const LINKEDIN_ADS: Rule = Rule {
+    name: "linkedin_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("li_fat_id", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const YANDEX_ADS: Rule = Rule {
+    name: "yandex_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[
+        TrackingParam::new("yclid", Analytics, Conservative),
+        TrackingParam::new("ysclid", Analytics, Conservative),
+    ],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const SNAPCHAT_ADS: Rule = Rule {
+    name: "snapchat_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("ScCid", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const PINTEREST_ADS: Rule = Rule {
+    name: "pinterest_ads",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("epik", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const IMPACT: Rule = Rule {
+    name: "impact",
+    category: Affiliate,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("irclickid", Affiliate, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+/// Click identifiers without a single well known network
+pub const AD_CLICK_IDS: Rule = Rule {
+    name: "ad_click_ids",
+    category: Analytics,
+    hosts: &["*"],
+    tracking_params: &[TrackingParam::new("rb_clickid", Analytics, Conservative)],
+    keep_params: None,
+    share_paths: &[],
+};
+
+pub const AD_NETWORKS: &[Rule] = &[
+    GOOGLE_ADS,
+    MICROSOFT_ADS,
+    META_ADS,
+    TIKTOK_ADS,
+    TWITTER_ADS,
+    LINKEDIN_ADS,
+    YANDEX_ADS,
+    SNAPCHAT_ADS,
+    PINTEREST_ADS,
+    IMPACT,
+    AD_CLICK_IDS,
+];
+
+const GOOGLE_HOSTS: &[&str] = &["google.*", "*.google.*"];
+
+/// Search parameters that change the results, everything else is removed from searches
+const GOOGLE_SEARCH_KEEP_PARAMS: &[&str] = &[
+    "q", "tbm", "tbs", "udm", "hl", "gl", "lr", "cr", "start", "num", "safe", "filter", "nfpr",
+];
+
+const GOOGLE_SEARCH_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("ved", Analytics, Conservative), // Click position of the result
+    TrackingParam::new("ei", Analytics, Conservative),  // Search session
+    TrackingParam::new("sca_esv", Analytics, Conservative),
+    TrackingParam::new("sca_upv", Analytics, Conservative),
+    TrackingParam::new("sxsrf", Analytics, Conservative),
+    TrackingParam::new("gs_lcrp", Analytics, Conservative),
+    TrackingParam::new("gs_lp", Analytics, Conservative),
+    TrackingParam::new("gs_ssp", Analytics, Conservative),
+    TrackingParam::new("aqs", Analytics, Conservative),
+    TrackingParam::new("uact", Analytics, Conservative),
+    TrackingParam::new("iflsig", Analytics, Conservative),
+    TrackingParam::new("fbs", Analytics, Conservative),
+    TrackingParam::new("ictx", Analytics, Conservative),
+    TrackingParam::new("oq", Referral, Standard), // What was typed before autocompletion
+    TrackingParam::new("sourceid", Referral, Standard),
+    TrackingParam::new("source", Referral, Standard),
+    TrackingParam::new("client", Referral, Standard),
+    TrackingParam::new("sclient", Referral, Standard),
+    TrackingParam::new("rlz", Referral, Standard), // Installation of the browser
+    TrackingParam::new("sa", Referral, Standard),
+    TrackingParam::new("bih", Functional, Standard), // Browser window size
+    TrackingParam::new("biw", Functional, Standard),
+    TrackingParam::new("dpr", Functional, Standard),
+    TrackingParam::new("prmd", Functional, Standard),
+    TrackingParam::new("ie", Functional, Standard), // Encoding of the query
+];
+
+const GOOGLE_MAPS_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("entry", Referral, Conservative),
+    TrackingParam::new("g_ep", Analytics, Conservative),
+    TrackingParam::new("g_st", Referral, Conservative),
+    TrackingParam::new("shorturl", Referral, Conservative),
+    TrackingParam::new("coh", Analytics, Conservative),
+    TrackingParam::new("skid", ShareId, Conservative),
+];
+
+const GOOGLE_DOCS_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("usp", Referral, Conservative), // e.g. `sharing` or `drive_link`
+    TrackingParam::new("ouid", ShareId, Conservative), // Owner of the shared file
+    TrackingParam::new("rtpof", Referral, Conservative),
+    TrackingParam::new("sd", Referral, Conservative),
+    TrackingParam::new("pli", Referral, Standard),
+];
+
+const GOOGLE_PLAY_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("referrer", Referral, Conservative), // Install referrer, see below
+    TrackingParam::new("pcampaignid", Analytics, Conservative),
+    TrackingParam::new("pli", Referral, Standard),
+];
+
+/// Parameters found in the URL encoded install referrer of Google Play links
+const GOOGLE_PLAY_REFERRER_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("gclid", Analytics, Conservative),
+    TrackingParam::new("adjust_*", Analytics, Conservative),
+    TrackingParam::new("af_*", Analytics, Conservative),
+];
+
+/// Google Search, Maps, Docs, Drive and Play on all country domains. Searches are reduced to
+/// the query and parameters that change the results.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Google;
+
+impl Provider for Google {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    fn category(&self) -> Category {
+        Analytics
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        host_matches(url.host_str().unwrap_or(""), GOOGLE_HOSTS)
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        let host = url.host_str().unwrap_or("");
+        let strictness = parser.strictness_for(self.name());
+
+        if host_matches(host, &["docs.google.com", "drive.google.com"]) {
+            parser.clean_url(url, GOOGLE_HOSTS, GOOGLE_DOCS_PARAMS, strictness)
+        } else if host == "play.google.com" {
+            clean_google_play_url(parser, url, strictness)
+        } else if host.starts_with("maps.") || url.path().starts_with("/maps") {
+            parser.clean_url(url, GOOGLE_HOSTS, GOOGLE_MAPS_PARAMS, strictness)
+        } else if matches!(url.path(), "/search" | "/webhp" | "/")
+            && (strictness > Conservative || parser.keep_list_enabled(self.name()))
+        {
+            parser.clean_url_keep_only(
+                url,
+                GOOGLE_HOSTS,
+                GOOGLE_SEARCH_KEEP_PARAMS,
+                GOOGLE_SEARCH_PARAMS,
+            )
+        } else {
+            parser.clean_url(url, GOOGLE_HOSTS, GOOGLE_SEARCH_PARAMS, strictness)
+        }
+    }
+}
+
+/// Removes the tracking params of a Google Play link. The install referrer is a whole URL
+/// encoded query string, each parameter in it is reported on its own.
+fn clean_google_play_url(parser: &Parser, url: &Url, strictness: Strictness) -> Option<Cleaned> {
+    let mut cleaned = parser.clean_url(url, GOOGLE_HOSTS, GOOGLE_PLAY_PARAMS, strictness)?;
+    let referrer = cleaned
+        .findings
+        .iter()
+        .find(|finding| finding.name == "referrer")
+        .map(|finding| finding.value.clone());
+
+    if let Some(referrer) = referrer {
+        for (name, value) in url::form_urlencoded::parse(referrer.as_bytes()) {
+            let category = GOOGLE_PLAY_REFERRER_PARAMS
+                .iter()
+                .find(|param| param_matches(param.name, &name))
+                .map_or(Referral, |param| param.category);
+            cleaned
+                .findings
+                .push(Finding::removed(name, value, category));
+        }
+    }
+    Some(cleaned)
+}
+
+/// Twitter and X. Intent and search links keep the parameters they need, like `text` or
+/// `q`, and the link shared with an intent is cleaned as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Twitter;
+
+impl Provider for Twitter {
+    fn name(&self) -> &str {
+        "twitter"
+    }
+
+    fn category(&self) -> Category {
+        ShareId
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        host_matches(url.host_str().unwrap_or(""), TWITTER_HOSTS)
+    }
+
+    fn clean(&self, parser: &Parser, parsed_url: &Url) -> Option<Cleaned> {
+        let segments: Vec<&str> = parsed_url.path_segments().map_or(vec![], Iterator::collect);
+        let is_intent = matches!(segments.as_slice(), ["intent", ..] | ["share"]);
+        let tracking_params = if is_intent {
+            TWITTER_INTENT_PARAMS
+        } else if segments.first() == Some(&"search") {
+            TWITTER_SEARCH_PARAMS
+        } else {
+            TWITTER_PARAMS
+        };
+
+        // Without a well known keep list, keep-list mode falls back to the block list
+        let strictness = parser.strictness_for(self.name());
+        let cleaned = parser.clean_url(parsed_url, TWITTER_HOSTS, tracking_params, strictness);
+        if !is_intent {
+            return cleaned;
+        }
+
+        let mut findings = Vec::new();
+        let mut url = match cleaned {
+            Some(cleaned) => {
+                findings = cleaned.findings;
+                Url::parse(&cleaned.url).ok()?
+            }
+            None => parsed_url.clone(),
+        };
+        if let Some(nested) = clean_nested_param(parser, &mut url, "url") {
+            findings.extend(nested.findings);
+        }
+
+        if findings.is_empty() && url == *parsed_url {
+            return None;
+        }
+        Some(Cleaned {
+            url: url.to_string(),
+            findings,
+        })
+    }
+}
+
+/// Cleans the URL in the query parameter `param` with all providers and writes it back
+fn clean_nested_param(parser: &Parser, url: &mut Url, param: &str) -> Option<Cleaned> {
+    let nested = url
+        .query_pairs()
+        .find(|(name, _)| name == param)
+        .and_then(|(_, value)| Url::parse(&value).ok())?;
+    let cleaned = parser.clean_nested_url(&nested)?;
+
+    // Only the nested URL is encoded again, the other pairs are kept as they were
+    let query: Vec<String> = url
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .map(
+            |raw| match url::form_urlencoded::parse(raw.as_bytes()).next() {
+                Some((name, _)) if name == param => {
+                    url::form_urlencoded::Serializer::new(String::new())
+                        .append_pair(param, &cleaned.url)
+                        .finish()
+                }
+                _ => raw.to_string(),
+            },
+        )
+        .collect();
+    url.set_query(Some(&query.join("&")));
+    Some(cleaned)
+}
+
+/// YouTube videos, shorts, live streams, embeds and `youtu.be` links. In keep-list mode
+/// each kind of link keeps the parameters it understands, like `t` and `list` for videos
+/// or `autoplay` for embeds. With `unwrap_attribution_links` set, `attribution_link?u=`
+/// redirects are replaced by the video they point to.
+#[derive(Debug, Clone, Copy)]
+pub struct YouTube {
+    pub unwrap_attribution_links: bool,
+}
+
+impl Default for YouTube {
+    fn default() -> Self {
+        Self {
+            unwrap_attribution_links: true,
+        }
+    }
+}
+
+impl Provider for YouTube {
+    fn name(&self) -> &str {
+        YOUTUBE_RULE.name
+    }
+
+    fn category(&self) -> Category {
+        YOUTUBE_RULE.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        YOUTUBE_RULE.matches(url)
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        if self.unwrap_attribution_links && url.path() == "/attribution_link" {
+            return unwrap_attribution_link(parser, url);
+        }
+        if !parser.keep_list_enabled(self.name()) {
+            return YOUTUBE_RULE.clean(parser, url);
+        }
+        parser.clean_url_keep_only(
+            url,
+            YOUTUBE_RULE.hosts,
+            youtube_keep_params(url),
+            YOUTUBE_RULE.tracking_params,
+        )
+    }
+}
+
+/// Parameters each kind of YouTube link needs, by the first path segment
+fn youtube_keep_params(url: &Url) -> &'static [&'static str] {
+    let first_segment = url.path_segments().and_then(|mut s| s.next()).unwrap_or("");
+    match (url.host_str().unwrap_or(""), first_segment) {
+        ("youtu.be" | "yt.be", _) => &["t", "start", "list", "index"],
+        (_, "watch") => &["v", "t", "start", "list", "index"],
+        (_, "shorts") => &[],
+        (_, "live") => &["t"],
+        (_, "embed") => &[
+            "start", "end", "autoplay", "loop", "playlist", "list", "mute", "controls", "rel",
+        ],
+        (_, "playlist") => &["list"],
+        (_, "results") => &["search_query", "sp"],
+        _ => YOUTUBE_RULE.keep_params.unwrap_or(&[]),
+    }
+}
+
+/// `attribution_link` redirects carry the target as a path like `/watch?v=ID&feature=share`,
+/// targets on other sites are not unwrapped
+fn unwrap_attribution_link(parser: &Parser, url: &Url) -> Option<Cleaned> {
+    let target = url
+        .query_pairs()
+        .find(|(name, _)| name == "u")
+        .and_then(|(_, value)| url.join(&value).ok())
+        .filter(|target| {
+            matches!(target.scheme(), "http" | "https") && YOUTUBE_RULE.matches(target)
+        })?;
+
+    let mut findings = vec![Finding::removed("redirect", url.path(), Referral)];
+    let url = match parser.clean_nested_url(&target) {
+        Some(cleaned) => {
+            findings.extend(cleaned.findings);
+            cleaned.url
+        }
+        None => target.to_string(),
+    };
+    Some(Cleaned { url, findings })
+}
+
+/// Instagram posts, reels and stories. In keep-list mode only posts keep `img_index`, the
+/// picture of a carousel. `/share/` links encode the sharer and are reported as requiring
+/// resolution.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Instagram;
+
+impl Provider for Instagram {
+    fn name(&self) -> &str {
+        INSTAGRAM_RULE.name
+    }
+
+    fn category(&self) -> Category {
+        INSTAGRAM_RULE.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        INSTAGRAM_RULE.matches(url)
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        let first_segment = url.path_segments().and_then(|mut s| s.next()).unwrap_or("");
+        let keep_params: &[&str] = match first_segment {
+            "p" | "tv" => &["img_index"],
+            "reel" | "reels" | "stories" | "share" => &[],
+            _ => INSTAGRAM_RULE.keep_params.unwrap_or(&[]),
+        };
+        Rule {
+            keep_params: Some(keep_params),
+            ..INSTAGRAM_RULE
+        }
+        .clean(parser, url)
+    }
+}
+
+/// TikTok videos and profiles. With `normalize_paths` set, path segments after the ID of
+/// `/@user/video/<ID>` and `/@user/photo/<ID>` links are dropped.
+#[derive(Debug, Clone, Copy)]
+pub struct TikTok {
+    pub normalize_paths: bool,
+}
+
+impl Default for TikTok {
+    fn default() -> Self {
+        Self {
+            normalize_paths: true,
+        }
+    }
+}
+
+impl Provider for TikTok {
+    fn name(&self) -> &str {
+        TIKTOK_RULE.name
+    }
+
+    fn category(&self) -> Category {
+        TIKTOK_RULE.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        TIKTOK_RULE.matches(url)
+    }
+
+    fn clean(&self, parser: &Parser, parsed_url: &Url) -> Option<Cleaned> {
+        let mut url = parsed_url.clone();
+        let mut findings = Vec::new();
+
+        if self.normalize_paths {
+            let segments: Vec<&str> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+            if let [user, kind @ ("video" | "photo"), id, _, ..] = segments.as_slice() {
+                if user.starts_with('@') {
+                    url.set_path(&format!("/{}/{}/{}", user, kind, id));
+                }
+            }
+        }
+
+        if let Some(cleaned) = TIKTOK_RULE.clean(parser, &url) {
+            url = Url::parse(&cleaned.url).ok()?;
+            findings.extend(cleaned.findings);
+        }
+
+        if findings.is_empty() && url == *parsed_url {
+            return None;
+        }
+        Some(Cleaned {
+            url: url.to_string(),
+            findings,
+        })
+    }
+}
+
+/// Amazon storefronts of all countries. Product URLs are reduced to `/dp/<ASIN>`,
+/// affiliate tags are reported and only removed if `remove_affiliate_tags` is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Amazon {
+    pub remove_affiliate_tags: bool,
+}
+
+impl Provider for Amazon {
+    fn name(&self) -> &str {
+        "amazon"
+    }
+
+    fn category(&self) -> Category {
+        Affiliate
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        host_matches(url.host_str().unwrap_or(""), AMAZON_HOSTS)
+    }
+
+    fn clean(&self, parser: &Parser, parsed_url: &Url) -> Option<Cleaned> {
+        let mut url = parsed_url.clone();
+        let mut findings = Vec::new();
+
+        if let Some((path, ref_value)) = canonical_amazon_path(url.path()) {
+            let dropped = dropped_segments(url.path(), &path);
+            if !dropped.is_empty() {
+                findings.push(Finding::removed("path", dropped, Unknown));
+            }
+            if let Some(value) = ref_value {
+                findings.push(Finding::removed("ref", value, Referral));
+            }
+            url.set_path(&path);
+        }
+
+        let mut tracking_params = AMAZON_PARAMS.to_vec();
+        if self.remove_affiliate_tags {
+            tracking_params.extend_from_slice(AMAZON_AFFILIATE_PARAMS);
+        }
+        let strictness = parser.strictness_for(self.name());
+        if let Some(cleaned) = parser.clean_url(&url, AMAZON_HOSTS, &tracking_params, strictness) {
+            url = Url::parse(&cleaned.url).ok()?;
+            findings.extend(cleaned.findings);
+        }
+
+        // Affiliate tags that were kept are still reported
+        for (name, value) in url.query_pairs() {
+            if let Some(param) = AMAZON_AFFILIATE_PARAMS.iter().find(|p| p.name == name) {
+                findings.push(Finding::flagged(name, value, param.category));
+            }
+        }
+
+        if findings.is_empty() && url == *parsed_url {
+            return None;
+        }
+        Some(Cleaned {
+            url: url.to_string(),
+            findings,
+        })
+    }
+}
+
+/// Reduces product paths like `/Product-Name/dp/B08N5WRWNW/ref=sr_1_1` to `/dp/B08N5WRWNW`
+/// and strips `ref=` segments from other paths. Returns the new path and the removed
+/// `ref` value, or `None` if the path is already clean.
+fn canonical_amazon_path(path: &str) -> Option<(String, Option<String>)> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let ref_value = segments
+        .iter()
+        .find_map(|segment| segment.strip_prefix("ref="))
+        .map(String::from);
+
+    let asin = segments.windows(2).find_map(|pair| match pair {
+        ["dp", asin] | ["product", asin] | ["d", asin] if is_asin(asin) => Some(*asin),
+        _ => None,
+    });
+
+    let canonical = match asin {
+        Some(asin) => format!("/dp/{}", asin),
+        None if ref_value.is_some() => {
+            let kept: Vec<&str> = segments
+                .iter()
+                .copied()
+                .filter(|segment| !segment.starts_with("ref="))
+                .collect();
+            format!("/{}", kept.join("/"))
+        }
+        None => return None,
+    };
+
+    if canonical == path {
+        return None;
+    }
+    Some((canonical, ref_value))
+}
+
+/// The segments of `path` that are not in `canonical`, joined by `/`. `ref=` segments are
+/// left out, they are reported on their own.
+fn dropped_segments(path: &str, canonical: &str) -> String {
+    let kept: Vec<&str> = canonical.split('/').collect();
+    path.split('/')
+        .filter(|s| !s.is_empty() && !s.starts_with("ref=") && !kept.contains(s))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Amazon Standard Identification Numbers are 10 characters of uppercase letters and digits
+fn is_asin(segment: &str) -> bool {
+    segment.len() == 10
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// An online shop whose item pages are reduced to the numeric item ID, e.g.
+/// `/itm/Some-Item-Title/123456789` to `/itm/123456789`
+#[derive(Debug, Clone, Copy)]
+pub struct Shop {
+    pub rule: Rule,
+    /// Path segment preceding the item ID, like `itm` for eBay
+    pub item_segment: &'static str,
+}
+
+impl Provider for Shop {
+    fn name(&self) -> &str {
+        self.rule.name
+    }
+
+    fn category(&self) -> Category {
+        self.rule.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        self.rule.matches(url)
+    }
+
+    fn clean(&self, parser: &Parser, parsed_url: &Url) -> Option<Cleaned> {
+        let mut url = parsed_url.clone();
+        let mut findings = Vec::new();
+
+        if let Some(path) = canonical_item_path(url.path(), self.item_segment) {
+            let dropped = dropped_segments(url.path(), &path);
+            if !dropped.is_empty() {
+                findings.push(Finding::removed("path", dropped, Unknown));
+            }
+            url.set_path(&path);
+        }
+
+        if let Some(cleaned) = self.rule.clean(parser, &url) {
+            url = Url::parse(&cleaned.url).ok()?;
+            findings.extend(cleaned.findings);
+        }
+
+        if findings.is_empty() && url == *parsed_url {
+            return None;
+        }
+        Some(Cleaned {
+            url: url.to_string(),
+            findings,
+        })
+    }
+}
+
+/// Keeps the path up to `item_segment` and the first numeric segment after it, dropping
+/// title slugs. Returns `None` if the path is no item page or already clean.
+fn canonical_item_path(path: &str, item_segment: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let start = segments.iter().position(|s| *s == item_segment)?;
+    let id = segments[start + 1..]
+        .iter()
+        .find(|s| s.chars().all(|c| c.is_ascii_digit()))?;
+
+    let canonical = format!("/{}/{}", segments[..=start].join("/"), id);
+    (canonical != path).then_some(canonical)
+}
+
+/// Medium articles. Besides the query parameters, legacy share hashes like `#.a1b2c3d4e`
+/// at the end of article links are removed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Medium;
+
+impl Provider for Medium {
+    fn name(&self) -> &str {
+        MEDIUM_RULE.name
+    }
+
+    fn category(&self) -> Category {
+        MEDIUM_RULE.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        MEDIUM_RULE.matches(url)
+    }
+
+    fn clean(&self, parser: &Parser, parsed_url: &Url) -> Option<Cleaned> {
+        let mut url = parsed_url.clone();
+        let mut findings = Vec::new();
+
+        if let Some(cleaned) = MEDIUM_RULE.clean(parser, &url) {
+            url = Url::parse(&cleaned.url).ok()?;
+            findings.extend(cleaned.findings);
+        }
+
+        if let Some(fragment) = url.fragment().filter(|f| is_share_hash(f)) {
+            findings.push(Finding::removed("#", fragment, ShareId));
+            url.set_fragment(None);
+        }
+
+        if findings.is_empty() {
+            return None;
+        }
+        Some(Cleaned {
+            url: url.to_string(),
+            findings,
+        })
+    }
+}
+
+/// Share hashes are a dot followed by letters and digits, paragraph anchors have no dot
+fn is_share_hash(fragment: &str) -> bool {
+    match fragment.strip_prefix('.') {
+        Some(hash) => !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric()),
+        None => false,
+    }
+}
+
+const MICROSOFT_HOSTS: &[&str] = &[
+    "bing.com",
+    "*.bing.com",
+    "msn.com",
+    "*.msn.com",
+    "onedrive.live.com",
+    "1drv.ms",
+    "*.sharepoint.com",
+    "*.safelinks.protection.outlook.com",
+    "statics.teams.cdn.office.net",
+];
+
+/// Parameters that change the results of a Bing search
+const BING_SEARCH_KEEP_PARAMS: &[&str] = &["q", "first", "count", "setlang", "cc", "mkt", "qft"];
+
+const BING_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("form", Referral, Conservative),
+    TrackingParam::new("FORM", Referral, Conservative),
+    TrackingParam::new("cvid", Analytics, Conservative), // Conversation ID of the session
+    TrackingParam::new("pq", Analytics, Conservative),   // Partial query while typing
+    TrackingParam::new("sk", Analytics, Conservative),
+    TrackingParam::new("sc", Analytics, Conservative),
+    TrackingParam::new("qs", Analytics, Conservative),
+    TrackingParam::new("sp", Analytics, Conservative),
+    TrackingParam::new("lq", Analytics, Conservative),
+    TrackingParam::new("ghc", Analytics, Conservative),
+    TrackingParam::new("ghsh", Analytics, Conservative),
+    TrackingParam::new("ghacc", Analytics, Conservative),
+    TrackingParam::new("ghpl", Analytics, Conservative),
+    TrackingParam::new("pc", Referral, Standard), // Partner code of the browser or app
+];
+
+const MSN_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("ocid", Referral, Conservative),
+    TrackingParam::new("cvid", Analytics, Conservative),
+    TrackingParam::new("ei", Analytics, Conservative),
+    TrackingParam::new("pc", Referral, Standard),
+];
+
+const ONEDRIVE_PARAMS: &[TrackingParam] = &[
+    TrackingParam::new("utm_*", Analytics, Conservative),
+    TrackingParam::new("e", ShareId, Conservative), // Identifies the sharing invitation
+    TrackingParam::new("CT", Analytics, Conservative), // Time the link was clicked
+    TrackingParam::new("OR", Referral, Conservative), // Where the link was opened from
+    TrackingParam::new("CID", Analytics, Conservative),
+    TrackingParam::new("at", Referral, Standard),
+];
+
+/// Access tokens of OneDrive and SharePoint links, the link does not open without them
+const ONEDRIVE_ACCESS_PARAMS: &[&str] = &["authkey", "tempauth", "share"];
+
+const OUTLOOK_SAFELINKS: Redirect = Redirect {
+    name: "microsoft",
+    hosts: &["*.safelinks.protection.outlook.com"],
+    paths: &["/"],
+    param: "url",
+};
+
+const TEAMS_SAFELINKS: Redirect = Redirect {
+    name: "microsoft",
+    hosts: &["statics.teams.cdn.office.net"],
+    paths: &["/evergreen-assets/safelinks"],
+    param: "url",
+};
+
+/// Bing, MSN, OneDrive and SharePoint. Outlook and Teams safe links are unwrapped. Access
+/// tokens of shared files are kept and reported with [`Action::Kept`](crate::Action::Kept).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Microsoft;
+
+impl Provider for Microsoft {
+    fn name(&self) -> &str {
+        "microsoft"
+    }
+
+    fn category(&self) -> Category {
+        Analytics
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        host_matches(url.host_str().unwrap_or(""), MICROSOFT_HOSTS)
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        let host = url.host_str().unwrap_or("");
+        let strictness = parser.strictness_for(self.name());
+
+        if OUTLOOK_SAFELINKS.matches(url) {
+            OUTLOOK_SAFELINKS.clean(parser, url)
+        } else if TEAMS_SAFELINKS.matches(url) {
+            TEAMS_SAFELINKS.clean(parser, url)
+        } else if host_matches(host, &["msn.com", "*.msn.com"]) {
+            parser.clean_url(url, MICROSOFT_HOSTS, MSN_PARAMS, strictness)
+        } else if host_matches(host, &["onedrive.live.com", "1drv.ms", "*.sharepoint.com"]) {
+            clean_onedrive_url(parser, url, strictness)
+        } else if matches!(url.path(), "/search" | "/")
+            && (strictness == Aggressive || parser.keep_list_enabled(self.name()))
+        {
+            parser.clean_url_keep_only(url, MICROSOFT_HOSTS, BING_SEARCH_KEEP_PARAMS, BING_PARAMS)
+        } else {
+            parser.clean_url(url, MICROSOFT_HOSTS, BING_PARAMS, strictness)
+        }
+    }
+}
+
+fn clean_onedrive_url(
+    parser: &Parser,
+    parsed_url: &Url,
+    strictness: Strictness,
+) -> Option<Cleaned> {
+    let mut cleaned = parser
+        .clean_url(parsed_url, MICROSOFT_HOSTS, ONEDRIVE_PARAMS, strictness)
+        .unwrap_or_else(|| Cleaned {
+            url: parsed_url.to_string(),
+            findings: Vec::new(),
+        });
+
+    let url = Url::parse(&cleaned.url).ok()?;
+    for (name, value) in url.query_pairs() {
+        if ONEDRIVE_ACCESS_PARAMS.contains(&name.as_ref()) {
+            cleaned
+                .findings
+                .push(Finding::kept(name, value, Functional));
+        }
+    }
+    (!cleaned.findings.is_empty()).then_some(cleaned)
+}
+
+/// The providers every `Parser` starts with at priority `0`, in the order they are tried.
+/// [`MASTODON`] is not part of them, it is registered at priority `-1`.
+pub fn builtin_providers() -> Vec<Box<dyn Provider>> {
+    let mut providers: Vec<Box<dyn Provider>> = vec![
+        Box::new(YouTube::default()),
+        Box::new(Twitter),
+        Box::new(Instagram),
+        Box::new(SPOTIFY),
+        Box::new(APPLE_MUSIC),
+        Box::new(DEEZER),
+        Box::new(TIDAL),
+        Box::new(SOUNDCLOUD),
+        Box::new(TWITCH),
+        Box::new(VIMEO),
+        Box::new(DAILYMOTION),
+        Box::new(RUMBLE),
+        Box::new(BILIBILI),
+        Box::new(SUBSTACK),
+        Box::new(FACEBOOK),
+        Box::new(TikTok::default()),
+        Box::new(LINKEDIN),
+        Box::new(REDDIT),
+        Box::new(Medium),
+        Box::new(NYTIMES),
+        Box::new(BLOOMBERG),
+        Box::new(GUARDIAN),
+        Box::new(BBC),
+        Box::new(PINTEREST),
+        Box::new(SNAPCHAT),
+        Box::new(TUMBLR),
+        Box::new(BLUESKY),
+        Box::new(BOOKING),
+        Box::new(AIRBNB),
+        Box::new(EXPEDIA),
+        Box::new(SKYSCANNER),
+        Box::new(STEAM_LINKFILTER),
+        Box::new(STEAM),
+        Box::new(APP_STORE),
+        Box::new(ITCH_IO),
+        Box::new(SLACK_REDIRECT),
+        Box::new(DISCORD),
+        Box::new(WHATSAPP),
+        Box::new(TELEGRAM),
+        Box::new(Google),
+        Box::new(Microsoft),
+        Box::new(Amazon::default()),
+        Box::new(EBAY),
+        Box::new(ALIEXPRESS),
+        Box::new(ETSY),
+        Box::new(WALMART),
+        Box::new(TEMU),
     ];
-    let tracking_params = [
-        "si", // Spotify Identifier
-        "utm_source",
-        "utm_medium",
-        "utm_campaign",
-        "utm_term",
-        "utm_content",
-        "feature",
-        "nd", // No Delay
-        "context",
-        "context_id",
-        "sp_cid",  // Spotify Campaign ID
-        "sp_ac",   // Spotify Ad Click
-        "sp_gaid", // Google Advertising ID
-        "sp_aid",  // Apple Identifier for Advertisers
-        "go",      // Generic Origin
-        "fbclid",  // Facebook Click Identifier
-        "product",
-        "referral",
-    ];
-    parser.parse_url(parsed_url, &valid_hosts, &tracking_params)
+    for rule in AD_NETWORKS.iter().chain(EMAIL_MARKETING) {
+        providers.push(Box::new(*rule));
+    }
+    providers
+}
+
+pub fn parse_twitter_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&Twitter, parsed_url)
+}
+
+pub fn parse_youtube_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&YouTube::default(), parsed_url)
+}
+
+pub fn parse_substack_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&SUBSTACK, parsed_url)
+}
+
+pub fn parse_spotify_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&SPOTIFY, parsed_url)
+}
+
+pub fn parse_apple_music_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&APPLE_MUSIC, parsed_url)
+}
+
+pub fn parse_deezer_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&DEEZER, parsed_url)
+}
+
+pub fn parse_tidal_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&TIDAL, parsed_url)
+}
+
+pub fn parse_soundcloud_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&SOUNDCLOUD, parsed_url)
+}
+
+pub fn parse_twitch_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&TWITCH, parsed_url)
+}
+
+pub fn parse_vimeo_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&VIMEO, parsed_url)
+}
+
+pub fn parse_dailymotion_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&DAILYMOTION, parsed_url)
+}
+
+pub fn parse_rumble_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&RUMBLE, parsed_url)
+}
+
+pub fn parse_bilibili_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&BILIBILI, parsed_url)
 }
 
 pub fn parse_instagram_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
-    let valid_hosts = ["www.instagram.com", "instagram.com"];
-    let tracking_params = [
-        "utm_source",
-        "utm_medium",
-        "utm_campaign",
-        "utm_term",
-        "utm_content",
-        "igshid",
-        "fbclid",
-        "_ga",
-        "_gid",
-    ];
-    parser.parse_url(parsed_url, &valid_hosts, &tracking_params)
+    parser.parse_with(&Instagram, parsed_url)
+}
+
+pub fn parse_facebook_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&FACEBOOK, parsed_url)
+}
+
+pub fn parse_tiktok_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&TikTok::default(), parsed_url)
+}
+
+pub fn parse_linkedin_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&LINKEDIN, parsed_url)
+}
+
+pub fn parse_reddit_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&REDDIT, parsed_url)
+}
+
+pub fn parse_medium_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&Medium, parsed_url)
+}
+
+pub fn parse_nytimes_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&NYTIMES, parsed_url)
+}
+
+pub fn parse_bloomberg_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&BLOOMBERG, parsed_url)
+}
+
+pub fn parse_guardian_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&GUARDIAN, parsed_url)
+}
+
+pub fn parse_bbc_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&BBC, parsed_url)
+}
+
+pub fn parse_pinterest_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&PINTEREST, parsed_url)
+}
+
+pub fn parse_snapchat_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&SNAPCHAT, parsed_url)
+}
+
+pub fn parse_tumblr_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&TUMBLR, parsed_url)
+}
+
+pub fn parse_bluesky_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&BLUESKY, parsed_url)
+}
+
+pub fn parse_mastodon_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&MASTODON, parsed_url)
+}
+
+pub fn parse_booking_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&BOOKING, parsed_url)
+}
+
+pub fn parse_airbnb_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&AIRBNB, parsed_url)
+}
+
+pub fn parse_expedia_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&EXPEDIA, parsed_url)
+}
+
+pub fn parse_skyscanner_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&SKYSCANNER, parsed_url)
+}
+
+pub fn parse_steam_linkfilter_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&STEAM_LINKFILTER, parsed_url)
+}
+
+pub fn parse_steam_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&STEAM, parsed_url)
+}
+
+pub fn parse_app_store_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&APP_STORE, parsed_url)
+}
+
+pub fn parse_itch_io_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&ITCH_IO, parsed_url)
+}
+
+pub fn parse_slack_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&SLACK_REDIRECT, parsed_url)
+}
+
+pub fn parse_discord_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&DISCORD, parsed_url)
+}
+
+pub fn parse_whatsapp_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&WHATSAPP, parsed_url)
+}
+
+pub fn parse_telegram_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&TELEGRAM, parsed_url)
+}
+
+pub fn parse_google_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&Google, parsed_url)
+}
+
+pub fn parse_microsoft_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&Microsoft, parsed_url)
+}
+
+pub fn parse_amazon_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&Amazon::default(), parsed_url)
+}
+
+pub fn parse_ebay_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&EBAY, parsed_url)
+}
+
+pub fn parse_aliexpress_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&ALIEXPRESS, parsed_url)
+}
+
+pub fn parse_etsy_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&ETSY, parsed_url)
+}
+
+pub fn parse_walmart_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&WALMART, parsed_url)
+}
+
+pub fn parse_temu_url(parser: &Parser, parsed_url: &Url) -> Option<String> {
+    parser.parse_with(&TEMU, parsed_url)
 }
@@ -0,0 +1,289 @@
+use super::*;
+use crate::parsing_core::{host_matches, path_matches, Parser};
+
+/// A provider knows which URLs belong to a site and how to remove their tracking tokens.
+///
+/// Implement this trait and register it with [`Parser::register_provider`] to handle sites
+/// the crate does not cover or to override a built-in provider.
+pub trait Provider: Send + Sync {
+    /// Name of the provider, registering a provider with an existing name replaces it
+    fn name(&self) -> &str;
+
+    /// Returns true if the URL belongs to this provider. The first matching provider is
+    /// the only one asked to clean the URL.
+    fn matches(&self, url: &Url) -> bool;
+
+    /// Returns the cleaned URL and what was removed, or `None` if there was nothing to remove
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned>;
+
+    /// What the parameters removed by the provider are mostly used for, e.g.
+    /// [`Category::Affiliate`] for shops or [`Category::Analytics`] for ad networks
+    fn category(&self) -> Category {
+        Category::Unknown
+    }
+
+    /// Global providers are applied to every URL before the matching site provider, for
+    /// parameters that appear on arbitrary sites
+    fn is_global(&self) -> bool {
+        false
+    }
+}
+
+/// A URL cleaned by a [`Provider`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cleaned {
+    pub url: String,
+    pub findings: Vec<Finding>,
+}
+
+/// A query parameter or path segment a provider removed or flagged
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub name: String,
+    pub value: String,
+    pub category: Category,
+    pub action: Action,
+    /// How sure the provider is that the parameter is used for tracking, from `0.0` to `1.0`
+    pub confidence: f32,
+    /// Name of the provider that reported the finding, set by the parser
+    pub provider: String,
+}
+
+impl Finding {
+    /// A parameter that is known to be used for tracking and was removed
+    pub fn removed(name: impl Into<String>, value: impl Into<String>, category: Category) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            category,
+            action: Action::Removed,
+            confidence: 1.0,
+            provider: String::new(),
+        }
+    }
+
+    /// A parameter that is known to be used for tracking, but was left in the URL
+    pub fn flagged(name: impl Into<String>, value: impl Into<String>, category: Category) -> Self {
+        Self {
+            action: Action::Flagged,
+            ..Self::removed(name, value, category)
+        }
+    }
+
+    /// A parameter that identifies the user, but was left in the URL because the link does
+    /// not work without it, e.g. an access token
+    pub fn kept(name: impl Into<String>, value: impl Into<String>, category: Category) -> Self {
+        Self {
+            action: Action::Kept,
+            ..Self::removed(name, value, category)
+        }
+    }
+
+    /// A share link path identifying the sharer, only resolving it gives a clean URL
+    pub fn requires_resolution(path: impl Into<String>) -> Self {
+        Self {
+            action: Action::RequiresResolution,
+            ..Self::removed("path", path, Category::ShareId)
+        }
+    }
+}
+
+/// What happened to a parameter in a [`Finding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Removed,
+    /// Reported as a likely tracker, but left in the URL
+    Flagged,
+    /// Left in the URL because the link needs it
+    Kept,
+    /// The URL is a share link identifying the sharer that has to be opened to get the
+    /// URL it points to
+    RequiresResolution,
+}
+
+/// What a tracking parameter is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Campaign and click tracking, e.g. `utm_source` or `fbclid`
+    Analytics,
+    /// Where the link was shared from, e.g. `ref_src` or `feature`
+    Referral,
+    /// Identifies who gets credited for a purchase or sign-up
+    Affiliate,
+    /// Identifies the session or user that shared the link, e.g. `si` or `igshid`
+    ShareId,
+    /// Parameters that may change what the page shows, e.g. Spotify's `context`
+    Functional,
+    /// Not a known tracking parameter, e.g. removed because it is not on a keep list
+    Unknown,
+}
+
+/// How much a parser strips, every level includes the parameters of the levels below
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strictness {
+    /// Only parameters that are without doubt used for tracking
+    Conservative,
+    /// Also share identifiers and parameters that are rarely needed
+    #[default]
+    Standard,
+    /// Everything that is not required to open the link
+    Aggressive,
+}
+
+/// A tracking parameter with the lowest strictness at which it is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackingParam {
+    /// Name of the parameter, `pd_rd_*` matches all names starting with `pd_rd_`
+    pub name: &'static str,
+    pub category: Category,
+    pub strictness: Strictness,
+}
+
+impl TrackingParam {
+    pub const fn new(name: &'static str, category: Category, strictness: Strictness) -> Self {
+        Self {
+            name,
+            category,
+            strictness,
+        }
+    }
+}
+
+/// A provider removing a fixed list of query parameters from the URLs of some hosts
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub name: &'static str,
+    /// What the tracking parameters of the provider are mostly used for
+    pub category: Category,
+    /// Hosts of the provider, `*.example.com` matches all subdomains of `example.com`,
+    /// `example.*` all country domains and a rule for `*` is global
+    pub hosts: &'static [&'static str],
+    pub tracking_params: &'static [TrackingParam],
+    /// Parameters needed for the link to work, only these survive in keep-list mode.
+    /// `None` if the functional parameters of the provider are not well known.
+    pub keep_params: Option<&'static [&'static str]>,
+    /// Paths of share links that identify the sharer and are reported as requiring
+    /// resolution, `*` matches any path segment and `#` numeric ones, e.g. `/share/*` or
+    /// `vm.tiktok.com/*`
+    pub share_paths: &'static [&'static str],
+}
+
+impl Provider for Rule {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        host_matches(url.host_str().unwrap_or(""), self.hosts)
+    }
+
+    fn is_global(&self) -> bool {
+        self.hosts.contains(&"*")
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        let cleaned = match self.keep_params {
+            Some(keep_params) if parser.keep_list_enabled(self.name) => {
+                parser.clean_url_keep_only(url, self.hosts, keep_params, self.tracking_params)
+            }
+            _ => {
+                let strictness = parser.strictness_for(self.name);
+                parser.clean_url(url, self.hosts, self.tracking_params, strictness)
+            }
+        };
+
+        if !parser.reports_share_links() || !path_matches(url, self.share_paths) {
+            return cleaned;
+        }
+        let mut cleaned = cleaned.unwrap_or_else(|| Cleaned {
+            url: url.to_string(),
+            findings: Vec::new(),
+        });
+        cleaned
+            .findings
+            .push(Finding::requires_resolution(url.path()));
+        Some(cleaned)
+    }
+}
+
+/// A provider for software running on any host, like Mastodon instances, that is
+/// recognized by the shape of its paths instead of its hosts
+#[derive(Debug, Clone, Copy)]
+pub struct PathRule {
+    /// The rule cleaning matching URLs, its hosts should be `["*"]`
+    pub rule: Rule,
+    /// Paths of the site, `*` matches any path segment, `#` numeric ones and `@*` segments
+    /// starting with `@`, e.g. `/@*/#` for Mastodon posts
+    pub paths: &'static [&'static str],
+}
+
+impl Provider for PathRule {
+    fn name(&self) -> &str {
+        self.rule.name
+    }
+
+    fn category(&self) -> Category {
+        self.rule.category
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        self.rule.matches(url) && path_matches(url, self.paths)
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        self.rule.clean(parser, url)
+    }
+}
+
+/// A provider unwrapping redirect links like `https://slack-redir.net/link?url=<target>`.
+/// The target is cleaned by the providers it belongs to, targets that are not `http` or
+/// `https` URLs are left wrapped.
+#[derive(Debug, Clone, Copy)]
+pub struct Redirect {
+    pub name: &'static str,
+    pub hosts: &'static [&'static str],
+    /// Paths of the redirect links, `*` matches any path segment
+    pub paths: &'static [&'static str],
+    /// Query parameter containing the target URL
+    pub param: &'static str,
+}
+
+impl Provider for Redirect {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn category(&self) -> Category {
+        Category::Referral
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        host_matches(url.host_str().unwrap_or(""), self.hosts) && path_matches(url, self.paths)
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        let target = url
+            .query_pairs()
+            .find(|(name, _)| name == self.param)
+            .and_then(|(_, value)| Url::parse(&value).ok())
+            .filter(|target| matches!(target.scheme(), "http" | "https"))?;
+
+        let mut findings = vec![Finding::removed(
+            "redirect",
+            url.host_str().unwrap_or(""),
+            Category::Referral,
+        )];
+        let url = match parser.clean_nested_url(&target) {
+            Some(cleaned) => {
+                findings.extend(cleaned.findings);
+                cleaned.url
+            }
+            None => target.to_string(),
+        };
+        Some(Cleaned { url, findings })
+    }
+}
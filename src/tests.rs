@@ -459,3 +459,2105 @@ fn test_clean_urls_and_get_removed_part() {
     )];
     assert_eq!(clean_urls_and_get_removed_part(&input), Some(expected));
 }
+
+#[test]
+fn test_schemeless_url_ignored_by_default() {
+    let input = String::from("Video: youtu.be/dQw4w9WgXcQ?si=abcdef");
+    assert_eq!(clean_urls_from_any_text(&input), None);
+}
+
+#[test]
+fn test_schemeless_url_parse_any_text() {
+    let mut parser = Parser::new();
+    parser.url_must_have_scheme(false);
+    let input = "Song: open.spotify.com/track/123?si=abc and video youtu.be/dQw4w9WgXcQ?si=abcdef";
+    let expected = vec![
+        (
+            "open.spotify.com/track/123".to_string(),
+            "?si=abc".to_string(),
+        ),
+        ("youtu.be/dQw4w9WgXcQ".to_string(), "?si=abcdef".to_string()),
+    ];
+    let reports = parser.parse_any_text(input).unwrap();
+    let cleaned: Vec<_> = reports
+        .into_iter()
+        .map(|report| (report.cleaned, report.removed))
+        .collect();
+    assert_eq!(cleaned, expected);
+}
+
+#[test]
+fn test_schemeless_url_replaced_in_place() {
+    let mut parser = Parser::new();
+    parser.url_must_have_scheme(false);
+    let mut input = String::from(
+        "Tweet www.x.com/user/status/123?s=20 and https://twitter.com/user/status/456?t=abc",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "Tweet www.x.com/user/status/123 and https://twitter.com/user/status/456"
+    );
+}
+
+#[test]
+fn test_schemeless_url_without_path() {
+    let mut parser = Parser::new();
+    parser.url_must_have_scheme(false);
+    let mut input = String::from("Video youtube.com?v=abc&si=x and youtube.com/?v=def&si=y");
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "Video youtube.com?v=abc and youtube.com/?v=def");
+}
+
+#[test]
+fn test_schemeless_url_unknown_domain() {
+    let mut parser = Parser::new();
+    parser.url_must_have_scheme(false);
+    let mut input = String::from("See example.com/page?utm_source=test");
+    assert_eq!(parser.sanitize_in_place(&mut input), None);
+    assert_eq!(input, "See example.com/page?utm_source=test");
+}
+
+struct ExampleProvider;
+
+impl Provider for ExampleProvider {
+    fn name(&self) -> &str {
+        "example"
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        url.host_str() == Some("www.youtube.com")
+    }
+
+    fn clean(&self, parser: &Parser, url: &Url) -> Option<Cleaned> {
+        let tracking_params = [TrackingParam::new(
+            "ref",
+            Category::Referral,
+            Strictness::Conservative,
+        )];
+        let strictness = parser.strictness_for(self.name());
+        parser.clean_url(url, &["www.youtube.com"], &tracking_params, strictness)
+    }
+}
+
+#[test]
+fn test_provider_with_higher_priority_overrides_builtin() {
+    let mut parser = Parser::new();
+    parser.register_provider(ExampleProvider, 1);
+    assert_eq!(parser.provider_names()[0], "example");
+
+    let reports = parser
+        .parse_any_text("https://www.youtube.com/watch?v=abc&ref=home&si=123")
+        .unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].provider, "example");
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.youtube.com/watch?v=abc&si=123"
+    );
+    assert_eq!(
+        reports[0].findings,
+        vec![Finding {
+            provider: "example".to_string(),
+            ..Finding::removed("ref", "home", Category::Referral)
+        }]
+    );
+
+    // The builtin provider is not asked once the custom provider matched
+    assert_eq!(
+        parser.parse_any_text("https://www.youtube.com/watch?v=abc&si=123"),
+        None
+    );
+}
+
+#[test]
+fn test_provider_with_lower_priority_runs_after_builtins() {
+    let mut parser = Parser::new();
+    parser.register_provider(ExampleProvider, -1);
+    assert_eq!(parser.provider_names().last(), Some(&"example"));
+
+    let mut input = String::from("https://www.youtube.com/watch?v=abc&ref=home&si=123");
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "https://www.youtube.com/watch?v=abc&ref=home");
+}
+
+#[test]
+fn test_custom_host_provider_before_mastodon() {
+    const SOCIAL: Rule = Rule {
+        name: "social",
+        category: Category::ShareId,
+        hosts: &["social.example"],
+        tracking_params: &[
+            TrackingParam::new("ref", Category::Referral, Strictness::Conservative),
+            TrackingParam::new("utm_*", Category::Analytics, Strictness::Conservative),
+        ],
+        keep_params: None,
+        share_paths: &[],
+    };
+    let mut parser = Parser::new();
+    parser.register_provider(SOCIAL, 0);
+
+    let reports = parser
+        .parse_any_text("https://social.example/@team/123?ref=x&utm_source=y")
+        .unwrap();
+    assert_eq!(reports[0].provider, "social");
+    assert_eq!(reports[0].cleaned, "https://social.example/@team/123");
+}
+
+#[test]
+fn test_provider_categories() {
+    let affiliate: Vec<String> = builtin_providers()
+        .iter()
+        .filter(|provider| provider.category() == Category::Affiliate)
+        .map(|provider| provider.name().to_string())
+        .collect();
+    assert!(affiliate.contains(&"amazon".to_string()));
+    assert!(affiliate.contains(&"ebay".to_string()));
+    assert!(!affiliate.contains(&"youtube".to_string()));
+
+    assert_eq!(MAILCHIMP.category(), Category::Analytics);
+    assert_eq!(MASTODON.category(), Category::ShareId);
+    assert_eq!(SLACK_REDIRECT.category(), Category::Referral);
+    assert_eq!(ExampleProvider.category(), Category::Unknown);
+}
+
+#[test]
+fn test_register_rule_provider() {
+    const INTRANET: Rule = Rule {
+        name: "intranet",
+        category: Category::Referral,
+        hosts: &["*.intranet.example"],
+        tracking_params: &[
+            TrackingParam::new("sso_redirect", Category::Referral, Strictness::Conservative),
+            TrackingParam::new("campaign", Category::Analytics, Strictness::Conservative),
+        ],
+        keep_params: None,
+        share_paths: &[],
+    };
+    let mut parser = Parser::new();
+    parser.register_provider(INTRANET, 0);
+
+    let mut input =
+        String::from("Login at https://wiki.intranet.example/page?id=1&sso_redirect=abc");
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "Login at https://wiki.intranet.example/page?id=1");
+
+    let untouched = "https://intranet.example/page?id=1&sso_redirect=abc";
+    assert_eq!(parser.parse_any_text(untouched), None);
+}
+
+#[test]
+fn test_register_provider_replaces_same_name() {
+    const TWITTER_KEEP_SHARE: Rule = Rule {
+        name: "twitter",
+        category: Category::ShareId,
+        hosts: &["twitter.com", "x.com"],
+        tracking_params: &[TrackingParam::new(
+            "utm_source",
+            Category::Analytics,
+            Strictness::Conservative,
+        )],
+        keep_params: None,
+        share_paths: &[],
+    };
+    let mut parser = Parser::new();
+    let provider_count = parser.provider_names().len();
+    parser.register_provider(TWITTER_KEEP_SHARE, 0);
+    assert_eq!(parser.provider_names().len(), provider_count);
+
+    let mut input = String::from("https://x.com/user/status/123?s=20&utm_source=test");
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "https://x.com/user/status/123?s=20");
+}
+
+#[test]
+fn test_conservative_strictness_keeps_share_ids() {
+    let mut parser = Parser::new();
+    parser.strictness(Strictness::Conservative);
+    let mut input = String::from(
+        "https://x.com/user/status/123?s=20&t=abc&utm_source=test and \
+         https://open.spotify.com/track/123?si=abc&context=spotify:album:1",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "https://x.com/user/status/123?s=20&t=abc and \
+         https://open.spotify.com/track/123?context=spotify:album:1"
+    );
+}
+
+#[test]
+fn test_aggressive_strictness() {
+    let url = "https://www.youtube.com/watch?v=abc&ab_channel=Someone&si=123";
+
+    let parser = Parser::new();
+    assert_eq!(
+        clean_urls_from_any_text(url),
+        Some(vec![
+            "https://www.youtube.com/watch?v=abc&ab_channel=Someone".to_string()
+        ])
+    );
+
+    let mut parser_aggressive = Parser::new();
+    parser_aggressive.strictness(Strictness::Aggressive);
+    let reports = parser_aggressive.parse_any_text(url).unwrap();
+    assert_eq!(reports[0].cleaned, "https://www.youtube.com/watch?v=abc");
+    let categories: Vec<_> = reports[0].findings.iter().map(|f| f.category).collect();
+    assert_eq!(categories, vec![Category::Functional, Category::ShareId]);
+
+    // parse functions use the strictness of the parser as well
+    let parsed_url = Url::parse(url).unwrap();
+    assert_eq!(
+        parse_youtube_url(&parser, &parsed_url),
+        Some("https://www.youtube.com/watch?v=abc&ab_channel=Someone".to_string())
+    );
+}
+
+#[test]
+fn test_provider_strictness_override() {
+    let mut parser = Parser::new();
+    parser.provider_strictness("twitter", Strictness::Conservative);
+    assert_eq!(parser.strictness_for("twitter"), Strictness::Conservative);
+    assert_eq!(parser.strictness_for("youtube"), Strictness::Standard);
+
+    let mut input = String::from(
+        "https://x.com/user/status/123?s=20&utm_source=test https://youtu.be/abc?si=123",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "https://x.com/user/status/123?s=20 https://youtu.be/abc"
+    );
+}
+
+#[test]
+fn test_disable_provider() {
+    let mut parser = Parser::new();
+    parser.enable_provider("twitter", false);
+    let input = "https://x.com/user/status/123?s=20 https://youtu.be/abc?si=123";
+    let reports = parser.parse_any_text(input).unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].provider, "youtube");
+
+    parser.enable_provider("twitter", true);
+    assert_eq!(parser.parse_any_text(input).unwrap().len(), 2);
+}
+
+#[test]
+fn test_keep_list_mode() {
+    let mut parser = Parser::new();
+    parser.keep_list_mode("youtube", true);
+    let reports = parser
+        .parse_any_text(
+            "https://www.youtube.com/watch?v=abc&list=PL1&index=2&t=42&si=123&new_tracker=x",
+        )
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.youtube.com/watch?v=abc&list=PL1&index=2&t=42"
+    );
+    let removed: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| (finding.name.as_str(), finding.category))
+        .collect();
+    assert_eq!(
+        removed,
+        vec![
+            ("si", Category::ShareId),
+            ("new_tracker", Category::Unknown)
+        ]
+    );
+}
+
+#[test]
+fn test_keep_list_mode_per_provider() {
+    let mut parser = Parser::new();
+    parser
+        .keep_list_mode("spotify", true)
+        .keep_list_mode("instagram", true)
+        // Twitter has no keep list, so the block list is used
+        .keep_list_mode("twitter", true);
+    let mut input = String::from(
+        "https://open.spotify.com/album/1?highlight=spotify:track:2&foo=bar \
+         https://www.instagram.com/p/ABC/?img_index=2&igsh=xyz \
+         https://x.com/user/status/1?s=20&foo=bar \
+         https://www.youtube.com/watch?v=abc&foo=bar",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "https://open.spotify.com/album/1?highlight=spotify:track:2 \
+         https://www.instagram.com/p/ABC/?img_index=2 \
+         https://x.com/user/status/1?foo=bar \
+         https://www.youtube.com/watch?v=abc&foo=bar"
+    );
+}
+
+#[test]
+fn test_heuristics_off_by_default() {
+    let parser = Parser::new();
+    let input = "https://shop.example/item?id=5&partner_clid=a8F3kLm0Qz7XyP2wR9tB";
+    assert_eq!(parser.parse_any_text(input), None);
+}
+
+#[test]
+fn test_heuristics_report_only() {
+    let mut parser = Parser::new();
+    parser.heuristics(Heuristics::Report);
+    let mut input = String::from(
+        "https://shop.example/item?id=5&partner_clid=dGhpcyBpcyBhIGJhc2U2NCBibG9i&mail=jane@example.com&page=2",
+    );
+    let reports = parser.parse_any_text(&input).unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].provider, "heuristics");
+    assert!(!reports[0].is_changed());
+    let flagged: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| (finding.name.as_str(), finding.action))
+        .collect();
+    assert_eq!(
+        flagged,
+        vec![("partner_clid", Action::Flagged), ("mail", Action::Flagged)]
+    );
+    assert!(reports[0]
+        .findings
+        .iter()
+        .all(|finding| finding.confidence >= 0.5));
+
+    // Flagged parameters are not removed
+    assert_eq!(parser.sanitize_in_place(&mut input), None);
+}
+
+#[test]
+fn test_heuristics_remove() {
+    let mut parser = Parser::new();
+    parser.heuristics(Heuristics::Remove);
+    let mut input = String::from(
+        "Look https://shop.example/item?id=5&hash=5d41402abc4b2a76b9719d911017c592&color=red",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "Look https://shop.example/item?id=5&color=red");
+}
+
+#[test]
+fn test_heuristics_not_used_for_known_providers() {
+    let mut parser = Parser::new();
+    parser.heuristics(Heuristics::Remove);
+    let input = "https://www.youtube.com/watch?v=dQw4w9WgXcQ&session_ref=aGVsbG8gd29ybGQgaGVsbG8";
+    assert_eq!(parser.parse_any_text(input), None);
+}
+
+#[test]
+fn test_heuristic_scores() {
+    use crate::heuristics::score;
+
+    assert!(score("gclid", "dGhpcyBpcyBhIGJhc2U2NCBibG9i").1 >= 0.7);
+    assert!(score("gclid", "123").1 < 0.5);
+    assert!(score("utm_source", "newsletter").1 < 0.5);
+    assert!(score("token", "dGhpcyBpcyBhIGJhc2U2NCBibG9i").1 >= 0.5);
+    assert!(
+        score(
+            "x",
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        )
+        .1 >= 0.7
+    );
+    assert!(score("share_id", "8d7Fq2LxV0pZr5Tn").1 > score("share_id", "1").1);
+    assert!(score("page", "2").1 < 0.5);
+    assert!(score("q", "rust programming").1 < 0.5);
+    assert!(score("v", "dQw4w9WgXcQ").1 < 0.5);
+    assert!(score("href", "abc").1 < 0.5);
+    assert!(score("product_id", "12345").1 < 0.5);
+    assert!(score("partner_clid", "8d7Fq2LxV0pZr5TnQw3e").1 >= 0.5);
+    assert!(score("xclid", "Zk39fL20aQpR7sYw").1 >= 0.5);
+    assert!(score("sessionid", "a1b2c3d4e5f6g7h8").1 >= 0.5);
+    assert!(score("color", "Zk39fL20aQpR7sYw").1 < 0.5);
+}
+
+#[test]
+fn test_heuristics_keep_functional_params() {
+    let mut parser = Parser::new();
+    parser.heuristics(Heuristics::Remove);
+    let input = "https://example.com/search?q=how+to+cook+pasta+quickly+at+home \
+                 https://example.com/search?q=how%20to%20cook%20pasta%20quickly%20at%20home \
+                 https://shop.example/item?product_id=12345&href=abc&pref=dark";
+    assert_eq!(parser.parse_any_text(input), None);
+}
+
+#[test]
+fn test_amazon_product_url() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.amazon.com/Some-Product-Name/dp/B08N5WRWNW/ref=sr_1_3?crid=2M096C61O4MLT&keywords=echo+dot&qid=1700000000&sprefix=echo%2Caps%2C150&sr=8-3").unwrap();
+    assert_eq!(
+        parse_amazon_url(&parser, &url),
+        Some("https://www.amazon.com/dp/B08N5WRWNW".to_string())
+    );
+
+    let url = Url::parse(
+        "https://www.amazon.de/gp/product/B08N5WRWNW?pd_rd_w=abc&pf_rd_p=def&pd_rd_r=ghi&th=1",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_amazon_url(&parser, &url),
+        Some("https://www.amazon.de/dp/B08N5WRWNW?th=1".to_string())
+    );
+
+    let url = Url::parse("https://www.amazon.co.uk/dp/B08N5WRWNW").unwrap();
+    assert_eq!(parse_amazon_url(&parser, &url), None);
+
+    let url = Url::parse("https://www.amazon.evil.com/dp/B08N5WRWNW?ref=abc").unwrap();
+    assert_eq!(parse_amazon_url(&parser, &url), None);
+}
+
+#[test]
+fn test_amazon_path_reported() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://www.amazon.de/gp/product/B08N5WRWNW?th=1")
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.amazon.de/dp/B08N5WRWNW?th=1"
+    );
+    assert_eq!(reports[0].findings.len(), 1);
+    assert_eq!(reports[0].findings[0].name, "path");
+    assert_eq!(reports[0].findings[0].value, "gp/product");
+    assert_eq!(reports[0].findings[0].action, Action::Removed);
+
+    let reports = parser
+        .parse_any_text("https://www.amazon.com/Some-Product-Name/dp/B08N5WRWNW/ref=sr_1_3")
+        .unwrap();
+    let removed: Vec<(&str, &str)> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| (finding.name.as_str(), finding.value.as_str()))
+        .collect();
+    assert_eq!(
+        removed,
+        vec![("path", "Some-Product-Name"), ("ref", "sr_1_3")]
+    );
+}
+
+#[test]
+fn test_amazon_search_url() {
+    let parser = Parser::new();
+    let url = Url::parse(
+        "https://www.amazon.co.jp/s/ref=nb_sb_noss?k=keyboard&crid=ABC&sprefix=key&ref=nb_sb_noss",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_amazon_url(&parser, &url),
+        Some("https://www.amazon.co.jp/s?k=keyboard".to_string())
+    );
+}
+
+#[test]
+fn test_amazon_variant_params_aggressive() {
+    let mut parser = Parser::new();
+    parser.provider_strictness("amazon", Strictness::Aggressive);
+    let url = Url::parse("https://www.amazon.com/dp/B08N5WRWNW?th=1&psc=1").unwrap();
+    assert_eq!(
+        parse_amazon_url(&parser, &url),
+        Some("https://www.amazon.com/dp/B08N5WRWNW".to_string())
+    );
+}
+
+#[test]
+fn test_amazon_affiliate_tag_reported() {
+    let parser = Parser::new();
+    let input = "https://www.amazon.com/dp/B08N5WRWNW?tag=someone-20&qid=123";
+    let reports = parser.parse_any_text(input).unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.amazon.com/dp/B08N5WRWNW?tag=someone-20"
+    );
+    let affiliate: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .filter(|finding| finding.category == Category::Affiliate)
+        .collect();
+    assert_eq!(affiliate.len(), 1);
+    assert_eq!(affiliate[0].value, "someone-20");
+    assert_eq!(affiliate[0].action, Action::Flagged);
+
+    // Only an affiliate tag is reported, but nothing changes
+    let reports = parser
+        .parse_any_text("https://www.amazon.com/dp/B08N5WRWNW?tag=someone-20")
+        .unwrap();
+    assert!(!reports[0].is_changed());
+    assert_eq!(
+        clean_urls_from_any_text("https://www.amazon.com/dp/B08N5WRWNW?tag=someone-20"),
+        None
+    );
+}
+
+#[test]
+fn test_amazon_affiliate_tag_removed() {
+    let mut parser = Parser::new();
+    parser.register_provider(
+        Amazon {
+            remove_affiliate_tags: true,
+        },
+        0,
+    );
+    let mut input = String::from(
+        "Buy https://amazon.de/dp/B08N5WRWNW?tag=someone-21&linkCode=ll1&ascsubtag=xyz",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "Buy https://amazon.de/dp/B08N5WRWNW");
+}
+
+#[test]
+fn test_parse_facebook_url_with_tracking() {
+    let parser = Parser::new();
+    let url = Url::parse(
+        "https://www.facebook.com/groups/123/posts/456/?__cft__[0]=AZX-abc&__tn__=%2CO%2CP-R&mibextid=Nif5oz",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_facebook_url(&parser, &url),
+        Some("https://www.facebook.com/groups/123/posts/456/".to_string())
+    );
+}
+
+#[test]
+fn test_parse_facebook_url_with_mixed_params() {
+    let parser = Parser::new();
+    let url =
+        Url::parse("https://m.facebook.com/story.php?story_fbid=123&id=456&rdid=abc&share_url=x")
+            .unwrap();
+    assert_eq!(
+        parse_facebook_url(&parser, &url),
+        Some("https://m.facebook.com/story.php?story_fbid=123&id=456".to_string())
+    );
+}
+
+#[test]
+fn test_parse_facebook_url_without_tracking() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.facebook.com/watch/?v=123").unwrap();
+    assert_eq!(parse_facebook_url(&parser, &url), None);
+}
+
+#[test]
+fn test_parse_messenger_and_threads_urls() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.messenger.com/t/123?utm_source=share&fbclid=abc").unwrap();
+    assert_eq!(
+        parse_facebook_url(&parser, &url),
+        Some("https://www.messenger.com/t/123".to_string())
+    );
+
+    let url = Url::parse("https://www.threads.net/@user/post/C1a2b3?xmt=AQGz&slof=1").unwrap();
+    assert_eq!(
+        parse_facebook_url(&parser, &url),
+        Some("https://www.threads.net/@user/post/C1a2b3".to_string())
+    );
+
+    let url = Url::parse("https://fb.watch/abc123/?mibextid=xyz").unwrap();
+    assert_eq!(
+        parse_facebook_url(&parser, &url),
+        Some("https://fb.watch/abc123/".to_string())
+    );
+}
+
+#[test]
+fn test_facebook_share_path_requires_resolution() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://www.facebook.com/share/p/1AbCdEfGh/?mibextid=WC7FNe")
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.facebook.com/share/p/1AbCdEfGh/"
+    );
+    let share = reports[0]
+        .findings
+        .iter()
+        .find(|finding| finding.action == Action::RequiresResolution)
+        .unwrap();
+    assert_eq!(share.value, "/share/p/1AbCdEfGh/");
+
+    // Share links without tracking params are reported, but not changed
+    let reports = parser
+        .parse_any_text("https://www.facebook.com/share/v/1AbCdEfGh/")
+        .unwrap();
+    assert!(!reports[0].is_changed());
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+}
+
+#[test]
+fn test_parse_tiktok_url() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.tiktok.com/@user/video/7234567890123456789?is_from_webapp=1&sender_device=pc&web_id=7234567890").unwrap();
+    assert_eq!(
+        parse_tiktok_url(&parser, &url),
+        Some("https://www.tiktok.com/@user/video/7234567890123456789".to_string())
+    );
+
+    let url = Url::parse("https://www.tiktok.com/@user/video/7234567890123456789?_r=1&_t=8abc&u_code=xyz&share_app_id=1233&checksum=abc&lang=en").unwrap();
+    assert_eq!(
+        parse_tiktok_url(&parser, &url),
+        Some("https://www.tiktok.com/@user/video/7234567890123456789?lang=en".to_string())
+    );
+
+    let url = Url::parse("https://www.tiktok.com/@user/video/7234567890123456789").unwrap();
+    assert_eq!(parse_tiktok_url(&parser, &url), None);
+}
+
+#[test]
+fn test_tiktok_path_normalization() {
+    let parser = Parser::new();
+    let url =
+        Url::parse("https://www.tiktok.com/@user/video/7234567890123456789/embed?_r=1").unwrap();
+    assert_eq!(
+        parse_tiktok_url(&parser, &url),
+        Some("https://www.tiktok.com/@user/video/7234567890123456789".to_string())
+    );
+
+    let mut parser = Parser::new();
+    parser.register_provider(
+        TikTok {
+            normalize_paths: false,
+        },
+        0,
+    );
+    let mut input =
+        String::from("https://www.tiktok.com/@user/video/7234567890123456789/embed?_r=1");
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "https://www.tiktok.com/@user/video/7234567890123456789/embed"
+    );
+}
+
+#[test]
+fn test_tiktok_share_link_requires_resolution() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text(
+            "https://vm.tiktok.com/ZMabc123/ and https://www.tiktok.com/t/ZTabc123/?_r=1",
+        )
+        .unwrap();
+    assert_eq!(reports.len(), 2);
+    assert!(!reports[0].is_changed());
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+    assert_eq!(reports[1].cleaned, "https://www.tiktok.com/t/ZTabc123/");
+    assert!(reports[1]
+        .findings
+        .iter()
+        .any(|finding| finding.action == Action::RequiresResolution));
+}
+
+#[test]
+fn test_parse_linkedin_url() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.linkedin.com/posts/user_title-activity-7098765432109876543-AbCd?utm_source=share&utm_medium=member_desktop&rcm=ACoAA").unwrap();
+    assert_eq!(
+        parse_linkedin_url(&parser, &url),
+        Some(
+            "https://www.linkedin.com/posts/user_title-activity-7098765432109876543-AbCd"
+                .to_string()
+        )
+    );
+
+    let url = Url::parse("https://de.linkedin.com/in/someone?trk=people-guest_people_search-card&originalSubdomain=de").unwrap();
+    assert_eq!(
+        parse_linkedin_url(&parser, &url),
+        Some("https://de.linkedin.com/in/someone".to_string())
+    );
+
+    let url = Url::parse("https://www.linkedin.com/in/someone").unwrap();
+    assert_eq!(parse_linkedin_url(&parser, &url), None);
+}
+
+#[test]
+fn test_linkedin_job_ids_preserved() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.linkedin.com/jobs/search/?currentJobId=3712345678&keywords=rust&trackingId=AbC%3D%3D&refId=xyz&lipi=urn").unwrap();
+    assert_eq!(
+        parse_linkedin_url(&parser, &url),
+        Some(
+            "https://www.linkedin.com/jobs/search/?currentJobId=3712345678&keywords=rust"
+                .to_string()
+        )
+    );
+
+    let mut parser = Parser::new();
+    parser.keep_list_mode("linkedin", true);
+    let mut input = String::from(
+        "https://www.linkedin.com/jobs/view/3712345678/?alternateChannel=search&refId=x&trackingId=y",
+    );
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "https://www.linkedin.com/jobs/view/3712345678/");
+}
+
+#[test]
+fn test_linkedin_email_tokens_removed() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.linkedin.com/comm/jobs/view/3712345678?trkEmail=eml-jobs&midToken=AQH&midSig=2x&eid=abc&otpToken=MTAw").unwrap();
+    assert_eq!(
+        parse_linkedin_url(&parser, &url),
+        Some("https://www.linkedin.com/comm/jobs/view/3712345678".to_string())
+    );
+}
+
+#[test]
+fn test_lnkd_in_requires_resolution() {
+    let parser = Parser::new();
+    let reports = parser.parse_any_text("https://lnkd.in/dAbC123x").unwrap();
+    assert!(!reports[0].is_changed());
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+    assert_eq!(reports[0].findings[0].value, "/dAbC123x");
+}
+
+#[test]
+fn test_parse_reddit_url() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.reddit.com/r/rust/comments/abc123/some_title/?utm_source=share&utm_medium=web3x&utm_name=web3xcss&utm_term=1&utm_content=share_button").unwrap();
+    assert_eq!(
+        parse_reddit_url(&parser, &url),
+        Some("https://www.reddit.com/r/rust/comments/abc123/some_title/".to_string())
+    );
+
+    let url = Url::parse("https://old.reddit.com/r/rust/comments/abc123/?share_id=xyz&rdt=4242&correlation_id=1&%24deep_link=true&ref_source=link&sort=new").unwrap();
+    assert_eq!(
+        parse_reddit_url(&parser, &url),
+        Some("https://old.reddit.com/r/rust/comments/abc123/?sort=new".to_string())
+    );
+
+    let url = Url::parse("https://redd.it/abc123?utm_source=share").unwrap();
+    assert_eq!(
+        parse_reddit_url(&parser, &url),
+        Some("https://redd.it/abc123".to_string())
+    );
+
+    let url = Url::parse("https://np.reddit.com/r/rust/comments/abc123/").unwrap();
+    assert_eq!(parse_reddit_url(&parser, &url), None);
+}
+
+#[test]
+fn test_reddit_share_link_requires_resolution() {
+    let input = "https://www.reddit.com/r/rust/s/AbCdEf123?utm_source=share";
+    let parser = Parser::new();
+    let reports = parser.parse_any_text(input).unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.reddit.com/r/rust/s/AbCdEf123"
+    );
+    assert!(reports[0]
+        .findings
+        .iter()
+        .any(|finding| finding.action == Action::RequiresResolution));
+
+    let mut parser = Parser::new();
+    parser.report_share_links(false);
+    let reports = parser.parse_any_text(input).unwrap();
+    assert!(reports[0]
+        .findings
+        .iter()
+        .all(|finding| finding.action == Action::Removed));
+    assert_eq!(
+        parser.parse_any_text("https://www.reddit.com/r/rust/s/AbCdEf123"),
+        None
+    );
+}
+
+#[test]
+fn test_google_search_reduced_to_query() {
+    let parser = Parser::new();
+    let url = Url::parse("https://www.google.com/search?q=rust+url+parsing&sca_esv=5a1b2c&sxsrf=ACQVn0&ei=abc123&ved=0ahUKEwi&uact=5&oq=rust+url&gs_lp=Egxnd3MtD&sclient=gws-wiz-serp&sourceid=chrome&ie=UTF-8").unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://www.google.com/search?q=rust+url+parsing".to_string())
+    );
+
+    let url =
+        Url::parse("https://www.google.de/search?q=rust&tbm=isch&hl=de&ved=0ahUKEwi").unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://www.google.de/search?q=rust&tbm=isch&hl=de".to_string())
+    );
+
+    let url = Url::parse("https://www.google.co.uk/search?q=rust&start=10").unwrap();
+    assert_eq!(parse_google_url(&parser, &url), None);
+
+    let url = Url::parse("https://www.google.com/search?q=a&ved=1&foo=bar").unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://www.google.com/search?q=a".to_string())
+    );
+}
+
+#[test]
+fn test_google_search_conservative() {
+    let mut parser = Parser::new();
+    parser.strictness(Strictness::Conservative);
+    let url = Url::parse(
+        "https://www.google.com/search?q=rust&ie=UTF-8&oq=rust&client=firefox-b-d&ved=0ahUKEwi",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some(
+            "https://www.google.com/search?q=rust&ie=UTF-8&oq=rust&client=firefox-b-d".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_google_country_domains() {
+    let parser = Parser::new();
+    for host in [
+        "google.com",
+        "www.google.co.jp",
+        "www.google.com.au",
+        "google.fr",
+    ] {
+        let url = Url::parse(&format!("https://{}/search?q=rust&ei=abc", host)).unwrap();
+        assert_eq!(
+            parse_google_url(&parser, &url),
+            Some(format!("https://{}/search?q=rust", host))
+        );
+    }
+    for host in ["google.evil.com", "notgoogle.com", "www.google.co.evil"] {
+        let url = Url::parse(&format!("https://{}/search?q=rust&ei=abc", host)).unwrap();
+        assert_eq!(parse_google_url(&parser, &url), None);
+    }
+}
+
+#[test]
+fn test_google_maps_url() {
+    let parser = Parser::new();
+    let url = Url::parse(
+        "https://www.google.com/maps/place/Berlin/@52.5,13.4,11z/data=!3m1?entry=ttu&g_ep=EgoyMDI0",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://www.google.com/maps/place/Berlin/@52.5,13.4,11z/data=!3m1".to_string())
+    );
+
+    let url = Url::parse("https://maps.google.de/maps?q=Berlin&entry=ttu").unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://maps.google.de/maps?q=Berlin".to_string())
+    );
+}
+
+#[test]
+fn test_google_docs_and_drive_url() {
+    let parser = Parser::new();
+    let url =
+        Url::parse("https://docs.google.com/document/d/1AbCdEf/edit?usp=sharing&ouid=1234567890")
+            .unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://docs.google.com/document/d/1AbCdEf/edit".to_string())
+    );
+
+    let url =
+        Url::parse("https://drive.google.com/file/d/1AbCdEf/view?usp=drive_link&resourcekey=0-abc")
+            .unwrap();
+    assert_eq!(
+        parse_google_url(&parser, &url),
+        Some("https://drive.google.com/file/d/1AbCdEf/view?resourcekey=0-abc".to_string())
+    );
+}
+
+#[test]
+fn test_email_marketing_params_removed_on_any_site() {
+    let mut input = String::from(
+        "Newsletter: https://blog.example.com/post?id=5&mc_cid=abc123&mc_eid=def456 \
+         https://shop.example.org/?_hsenc=p2ANqtz&_hsmi=12345&__hstc=1.2.3&__hssc=4.5&hsCtaTracking=x \
+         https://news.example.net/a?mkt_tok=NzEx&_kx=abc.XYZ",
+    );
+    assert!(replace_urls_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "Newsletter: https://blog.example.com/post?id=5 \
+         https://shop.example.org/ \
+         https://news.example.net/a"
+    );
+}
+
+#[test]
+fn test_email_marketing_platform_reported() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://example.com/?vero_id=1&oly_enc_id=2&wickedid=3&ss_source=4")
+        .unwrap();
+    let providers: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| finding.provider.as_str())
+        .collect();
+    assert_eq!(
+        providers,
+        vec!["vero", "omeda", "wicked_reports", "sharpspring"]
+    );
+    assert_eq!(reports[0].provider, "vero");
+}
+
+#[test]
+fn test_email_marketing_combined_with_site_provider() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://www.youtube.com/watch?v=abc&mc_cid=123&si=456")
+        .unwrap();
+    assert_eq!(reports[0].cleaned, "https://www.youtube.com/watch?v=abc");
+    assert_eq!(reports[0].provider, "youtube");
+    let providers: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| finding.provider.as_str())
+        .collect();
+    assert_eq!(providers, vec!["mailchimp", "youtube"]);
+}
+
+#[test]
+fn test_email_marketing_platform_disabled() {
+    let mut parser = Parser::new();
+    parser.enable_provider("mailchimp", false);
+    let mut input = String::from("https://example.com/?mc_cid=123&_kx=abc");
+    assert!(parser.sanitize_in_place(&mut input).is_some());
+    assert_eq!(input, "https://example.com/?mc_cid=123");
+}
+
+#[test]
+fn test_ad_click_ids_on_landing_pages() {
+    let input = "https://www.shop-example.com/products/running-shoes?variant=42&gclid=Cj0KCQjw8e-gBhD0ARIsAJiDsaW&gad_source=1 \
+        https://www.example-insurance.com/quote?msclkid=5f3c9b1e2a4d1b7c8e9f0a1b2c3d4e5f&utm_source=bing \
+        https://landing.example.org/offer?ttclid=E.C.P.CrEBxyz&lang=en \
+        https://example.co.uk/sale?fbclid=IwAR2x9z&ScCid=a1b2c3&epik=dj0yJnU9&li_fat_id=3f1c \
+        https://store.example.ru/item/1?yclid=1234567890&ysclid=lq8z1 \
+        https://brand.example.com/?irclickid=Q2a3Xy&rb_clickid=abc&twclid=2-7x&wbraid=CkAK&dclid=CJr";
+    let expected = vec![
+        "https://www.shop-example.com/products/running-shoes?variant=42".to_string(),
+        "https://www.example-insurance.com/quote?utm_source=bing".to_string(),
+        "https://landing.example.org/offer?lang=en".to_string(),
+        "https://example.co.uk/sale".to_string(),
+        "https://store.example.ru/item/1".to_string(),
+        "https://brand.example.com/".to_string(),
+    ];
+    assert_eq!(clean_urls_from_any_text(input), Some(expected));
+}
+
+#[test]
+fn test_ad_network_reported() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://example.com/?gclid=a&msclkid=b&fbclid=c&ttclid=d&irclickid=e")
+        .unwrap();
+    let networks: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| (finding.name.as_str(), finding.provider.as_str()))
+        .collect();
+    assert_eq!(
+        networks,
+        vec![
+            ("gclid", "google_ads"),
+            ("msclkid", "microsoft_ads"),
+            ("fbclid", "meta_ads"),
+            ("ttclid", "tiktok_ads"),
+            ("irclickid", "impact"),
+        ]
+    );
+}
+
+#[test]
+fn test_ad_click_ids_on_site_provider() {
+    let mut parser = Parser::new();
+    parser.enable_provider("meta_ads", false);
+    let reports = parser
+        .parse_any_text("https://www.instagram.com/p/ABC/?fbclid=abc&gclid=def")
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.instagram.com/p/ABC/?fbclid=abc"
+    );
+    let providers: Vec<_> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| (finding.name.as_str(), finding.provider.as_str()))
+        .collect();
+    // With the Meta pack disabled, site providers leave fbclid alone
+    assert_eq!(providers, vec![("gclid", "google_ads")]);
+
+    let reports = Parser::new()
+        .parse_any_text("https://www.youtube.com/watch?v=abc&fbclid=x")
+        .unwrap();
+    assert_eq!(reports[0].findings[0].provider, "meta_ads");
+}
+
+#[test]
+fn test_apple_music_url() {
+    let mut input = String::from("Listen to this song: https://music.apple.com/us/album/some-album/1234567890?i=1234567891&uo=4&app=music&ls=1&at=1000lHKX&ct=share");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Listen to this song: https://music.apple.com/us/album/some-album/1234567890?i=1234567891"
+    );
+}
+
+#[test]
+fn test_apple_music_url2() {
+    let input = String::from(
+        "Apple Music album: https://music.apple.com/de/album/1234567890?l=en&uo=4&itsct=music_box",
+    );
+    let expected = vec!["https://music.apple.com/de/album/1234567890?l=en".to_string()];
+    assert_eq!(clean_urls_from_any_text(&input), Some(expected));
+}
+
+#[test]
+fn test_deezer_url() {
+    let mut input = String::from("Listen to this song: https://www.deezer.com/en/track/123456789?utm_source=deezer&utm_content=track-123456789&utm_term=1234_1700000000&utm_medium=web&deferredFl=1");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Listen to this song: https://www.deezer.com/en/track/123456789"
+    );
+}
+
+#[test]
+fn test_deezer_url2() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("Deezer track: https://deezer.page.link/AbCdEf123")
+        .unwrap();
+    assert!(!reports[0].is_changed());
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+}
+
+#[test]
+fn test_tidal_url() {
+    let mut input = String::from("Listen to this song: https://tidal.com/browse/track/123456789?u");
+    assert_eq!(replace_urls_in_place(&mut input), None);
+
+    let mut input =
+        String::from("Listen to this song: https://tidal.com/browse/track/123456789?u=abc123");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Listen to this song: https://tidal.com/browse/track/123456789"
+    );
+}
+
+#[test]
+fn test_tidal_url2() {
+    let input = String::from(
+        "Tidal album: https://listen.tidal.com/album/123456789?u=abc&utm_source=share",
+    );
+    let expected = vec!["https://listen.tidal.com/album/123456789".to_string()];
+    assert_eq!(clean_urls_from_any_text(&input), Some(expected));
+}
+
+#[test]
+fn test_soundcloud_url() {
+    let mut input = String::from("Listen to this song: https://soundcloud.com/artist/track-name?si=0123456789abcdef0123456789abcdef&utm_source=clipboard&utm_medium=text&utm_campaign=social_sharing&in=artist/sets/playlist");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Listen to this song: https://soundcloud.com/artist/track-name"
+    );
+}
+
+#[test]
+fn test_soundcloud_url2() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("SoundCloud track: https://on.soundcloud.com/AbCdEf123?si=abc")
+        .unwrap();
+    assert_eq!(reports[0].cleaned, "https://on.soundcloud.com/AbCdEf123");
+    assert!(reports[0]
+        .findings
+        .iter()
+        .any(|finding| finding.action == Action::RequiresResolution));
+}
+
+#[test]
+fn test_youtube_music_url() {
+    let mut input = String::from(
+        "Listen to this song: https://music.youtube.com/watch?v=abc123&si=AbCdEfGh&feature=share",
+    );
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Listen to this song: https://music.youtube.com/watch?v=abc123"
+    );
+}
+
+#[test]
+fn test_ebay_url() {
+    let mut input = String::from("Look at this: https://www.ebay.com/itm/Vintage-Camera-Lens/123456789012?_trkparms=amclksrc%3DITM%26aid%3D1110006&_trksid=p2047675.c100005.m1851&hash=item1cbe5f8a2b:g:abcAAOSw&mkevt=1&mkcid=1&var=987654");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Look at this: https://www.ebay.com/itm/123456789012?var=987654"
+    );
+}
+
+#[test]
+fn test_ebay_path_reported() {
+    let reports = Parser::new()
+        .parse_any_text("https://www.ebay.com/itm/Vintage-Camera-Lens/123456789012")
+        .unwrap();
+    assert_eq!(reports[0].cleaned, "https://www.ebay.com/itm/123456789012");
+    assert_eq!(reports[0].findings.len(), 1);
+    assert_eq!(reports[0].findings[0].name, "path");
+    assert_eq!(reports[0].findings[0].value, "Vintage-Camera-Lens");
+}
+
+#[test]
+fn test_ebay_url2() {
+    let input =
+        String::from("eBay UK: https://www.ebay.co.uk/itm/123456789012?campid=5338&toolid=10001");
+    let expected = vec!["https://www.ebay.co.uk/itm/123456789012".to_string()];
+    assert_eq!(clean_urls_from_any_text(&input), Some(expected));
+}
+
+#[test]
+fn test_aliexpress_url() {
+    let mut input = String::from("Cheap: https://www.aliexpress.com/item/1005001234567890.html?spm=a2g0o.home.0.0.650c2145&scm=1007.1234&algo_pvid=abc-def&aff_fcid=123&aff_platform=portals-tool&pdp_npi=4%40dis%21EUR&sku_id=12000012345");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Cheap: https://www.aliexpress.com/item/1005001234567890.html?sku_id=12000012345"
+    );
+}
+
+#[test]
+fn test_etsy_url() {
+    let mut input = String::from("Handmade: https://www.etsy.com/de/listing/1234567890/handmade-ceramic-mug?click_key=abc123&click_sum=def456&ref=hp_rv-1&pro=1&frs=1&sts=1&ga_order=most_relevant&ga_search_type=all");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Handmade: https://www.etsy.com/de/listing/1234567890"
+    );
+}
+
+#[test]
+fn test_walmart_url() {
+    let mut input = String::from("Deal: https://www.walmart.com/ip/Some-Product-Name/123456789?athbdg=L1600&athcpid=123&from=/search&selected=true");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Deal: https://www.walmart.com/ip/123456789?selected=true"
+    );
+}
+
+#[test]
+fn test_walmart_url_without_item() {
+    let url = Url::parse("https://www.walmart.com/browse/electronics/3944?from=/search").unwrap();
+    assert_eq!(
+        parse_walmart_url(&Parser::new(), &url),
+        Some("https://www.walmart.com/browse/electronics/3944".to_string())
+    );
+}
+
+#[test]
+fn test_temu_url() {
+    let mut input = String::from("Cheap: https://www.temu.com/goods-name-g-601099512345678.html?_x_ads_channel=google&_x_sessn_id=abc123&refer_page_name=home&refer_page_id=10005&_bg_fs=1&goods_id=601099512345678");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Cheap: https://www.temu.com/goods-name-g-601099512345678.html?goods_id=601099512345678"
+    );
+}
+
+#[test]
+fn test_twitch_url() {
+    let mut input = String::from("Watch this clip: https://clips.twitch.tv/FunnyClipName-AbCdEf123?tt_medium=clips_api&tt_content=url and the VOD https://www.twitch.tv/videos/1234567890?t=1h2m3s&sr=a");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Watch this clip: https://clips.twitch.tv/FunnyClipName-AbCdEf123 and the VOD https://www.twitch.tv/videos/1234567890?t=1h2m3s"
+    );
+}
+
+#[test]
+fn test_vimeo_url() {
+    let mut input = String::from(
+        "Short film: https://vimeo.com/123456789?share=copy&fl=pl&fe=sh&embedded=true#t=42s",
+    );
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(input, "Short film: https://vimeo.com/123456789#t=42s");
+}
+
+#[test]
+fn test_dailymotion_url() {
+    let mut input = String::from("Video: https://www.dailymotion.com/video/x8abcde?playlist=x6hynp&start=90&utm_source=share&utm_medium=copy");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Video: https://www.dailymotion.com/video/x8abcde?playlist=x6hynp&start=90"
+    );
+}
+
+#[test]
+fn test_rumble_url() {
+    let url =
+        Url::parse("https://rumble.com/v4abcde-some-video.html?e9s=src_v1_ucp&start=30").unwrap();
+    assert_eq!(
+        parse_rumble_url(&Parser::new(), &url),
+        Some("https://rumble.com/v4abcde-some-video.html?start=30".to_string())
+    );
+}
+
+#[test]
+fn test_bilibili_url() {
+    let mut input = String::from("Video: https://www.bilibili.com/video/BV1xx411c7mD?p=2&t=120&spm_id_from=333.1007.tianma.1-1-1.click&vd_source=0123456789abcdef0123456789abcdef&share_source=copy_web&share_medium=android&bbid=XY123&ts=1700000000&from_spmid=search");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Video: https://www.bilibili.com/video/BV1xx411c7mD?p=2&t=120"
+    );
+}
+
+#[test]
+fn test_video_timestamps_in_keep_list_mode() {
+    let mut parser = Parser::new();
+    for name in ["twitch", "dailymotion", "bilibili"] {
+        parser.keep_list_mode(name, true);
+    }
+    let reports = parser
+        .parse_any_text(
+            "https://www.twitch.tv/videos/1?t=5m&foo=1 \
+             https://www.dailymotion.com/video/x8abcde?start=90&foo=1 \
+             https://www.bilibili.com/video/BV1xx411c7mD?t=120&foo=1",
+        )
+        .unwrap();
+    let cleaned: Vec<&str> = reports.iter().map(|r| r.cleaned.as_str()).collect();
+    assert_eq!(
+        cleaned,
+        vec![
+            "https://www.twitch.tv/videos/1?t=5m",
+            "https://www.dailymotion.com/video/x8abcde?start=90",
+            "https://www.bilibili.com/video/BV1xx411c7mD?t=120",
+        ]
+    );
+}
+
+#[test]
+fn test_b23_short_link() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("Video: https://b23.tv/AbCdEf1")
+        .unwrap();
+    assert_eq!(reports[0].provider, "bilibili");
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+}
+
+#[test]
+fn test_slack_redirect() {
+    let mut input = String::from("From Slack: https://slack-redir.net/link?url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3Dabc123%26feature%3Dshare");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(input, "From Slack: https://www.youtube.com/watch?v=abc123");
+}
+
+#[test]
+fn test_slack_redirect_findings() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text(
+            "https://slack-redir.net/link?url=https%3A%2F%2Fexample.com%2Fpage%3Fgclid%3Dabc",
+        )
+        .unwrap();
+    assert_eq!(reports[0].cleaned, "https://example.com/page");
+    assert_eq!(reports[0].provider, "slack");
+    let providers: Vec<&str> = reports[0]
+        .findings
+        .iter()
+        .map(|finding| finding.provider.as_str())
+        .collect();
+    assert_eq!(providers, vec!["slack", "google_ads"]);
+}
+
+#[test]
+fn test_slack_redirect_without_target() {
+    let url = Url::parse("https://slack-redir.net/link?foo=bar").unwrap();
+    assert_eq!(parse_slack_url(&Parser::new(), &url), None);
+}
+
+#[test]
+fn test_slack_redirect_to_javascript() {
+    let url = Url::parse("https://slack-redir.net/link?url=javascript%3Aalert(1)").unwrap();
+    assert_eq!(parse_slack_url(&Parser::new(), &url), None);
+}
+
+#[test]
+fn test_discord_url() {
+    let mut input = String::from(
+        "Join us: https://discord.gg/AbCdEf?utm_source=website&utm_medium=banner&event=12345",
+    );
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(input, "Join us: https://discord.gg/AbCdEf?event=12345");
+}
+
+#[test]
+fn test_discord_remote_auth_url() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("Scan this: https://discord.com/ra/AbCdEf123456")
+        .unwrap();
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+}
+
+#[test]
+fn test_whatsapp_url() {
+    let mut input = String::from("Message me: https://wa.me/15551234567?text=Hello&utm_source=site and https://api.whatsapp.com/send?phone=15551234567&utm_campaign=promo");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Message me: https://wa.me/15551234567?text=Hello and https://api.whatsapp.com/send?phone=15551234567"
+    );
+}
+
+#[test]
+fn test_telegram_url() {
+    let mut input = String::from(
+        "Channel: https://t.me/somechannel/123?ref=site&utm_source=share, bot: https://t.me/somebot?start=ref_12345",
+    );
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Channel: https://t.me/somechannel/123, bot: https://t.me/somebot?start=ref_12345"
+    );
+
+    let mut parser = Parser::new();
+    parser.provider_strictness("telegram", Strictness::Aggressive);
+    let url = Url::parse("https://t.me/somebot?start=ref_12345").unwrap();
+    assert_eq!(
+        parse_telegram_url(&parser, &url),
+        Some("https://t.me/somebot".to_string())
+    );
+}
+
+#[test]
+fn test_medium_url() {
+    let mut input = String::from("Read: https://medium.com/@someone/my-article-1a2b3c4d5e6f?source=friends_link&sk=0123456789abcdef0123456789abcdef");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Read: https://medium.com/@someone/my-article-1a2b3c4d5e6f"
+    );
+}
+
+#[test]
+fn test_medium_share_hash() {
+    let url = Url::parse("https://blog.medium.com/my-article-1a2b3c4d5e6f#.k4l5m6n7o").unwrap();
+    assert_eq!(
+        parse_medium_url(&Parser::new(), &url),
+        Some("https://blog.medium.com/my-article-1a2b3c4d5e6f".to_string())
+    );
+
+    // Paragraph anchors are kept
+    let url = Url::parse("https://medium.com/@someone/my-article-1a2b3c4d5e6f#a1b2").unwrap();
+    assert_eq!(parse_medium_url(&Parser::new(), &url), None);
+}
+
+#[test]
+fn test_nytimes_url() {
+    let mut input = String::from("Gift article: https://www.nytimes.com/2024/01/01/world/some-article.html?unlocked_article_code=1.AbC.dEf&smid=url-share&smtyp=cur&sgrp=c-cb");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Gift article: https://www.nytimes.com/2024/01/01/world/some-article.html?unlocked_article_code=1.AbC.dEf"
+    );
+}
+
+#[test]
+fn test_nytimes_gift_token_removed_when_aggressive() {
+    let mut parser = Parser::new();
+    parser.provider_strictness("nytimes", Strictness::Aggressive);
+    let url = Url::parse(
+        "https://www.nytimes.com/2024/01/01/world/some-article.html?unlocked_article_code=1.AbC.dEf&smid=url-share",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_nytimes_url(&parser, &url),
+        Some("https://www.nytimes.com/2024/01/01/world/some-article.html".to_string())
+    );
+}
+
+#[test]
+fn test_bloomberg_url() {
+    let mut input = String::from("News: https://www.bloomberg.com/news/articles/2024-01-01/some-headline?srnd=homepage-europe&cmpid=socialflow-twitter-business&leadSource=uverify%20wall&sref=AbCdEf12");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "News: https://www.bloomberg.com/news/articles/2024-01-01/some-headline"
+    );
+}
+
+#[test]
+fn test_guardian_url() {
+    let mut input = String::from("Story: https://www.theguardian.com/world/2024/jan/01/some-story?CMP=share_btn_tw&page=with:block-123");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Story: https://www.theguardian.com/world/2024/jan/01/some-story?page=with:block-123"
+    );
+}
+
+#[test]
+fn test_bbc_url() {
+    let mut input = String::from("BBC: https://www.bbc.co.uk/news/world-12345678?at_medium=RSS&at_campaign=KARANGA&at_custom1=twitter&ocid=socialflow_twitter");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(input, "BBC: https://www.bbc.co.uk/news/world-12345678");
+}
+
+#[test]
+fn test_pinterest_url() {
+    let mut input = String::from("Pin: https://www.pinterest.com/pin/123456789012345678/?invite_code=0123456789abcdef&sender=123456789012345678&sfo=1");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Pin: https://www.pinterest.com/pin/123456789012345678/"
+    );
+}
+
+#[test]
+fn test_pin_it_short_link() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("Pin: https://pin.it/AbCdEf123")
+        .unwrap();
+    assert_eq!(reports[0].provider, "pinterest");
+    assert_eq!(reports[0].findings[0].action, Action::RequiresResolution);
+}
+
+#[test]
+fn test_snapchat_url() {
+    let mut input = String::from("Spotlight: https://www.snapchat.com/spotlight/W7_EDlXWTBiXAEEniNoMPwAAYdGZ2Y2F0ZWt1AY6wbVJVAY6wbVIAAAAAAQ?share_id=AbCdEf&sid=0123456789&locale=en_US");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Spotlight: https://www.snapchat.com/spotlight/W7_EDlXWTBiXAEEniNoMPwAAYdGZ2Y2F0ZWt1AY6wbVJVAY6wbVIAAAAAAQ"
+    );
+}
+
+#[test]
+fn test_tumblr_url() {
+    let mut input =
+        String::from("Post: https://www.tumblr.com/someblog/123456789012/some-post?source=share");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Post: https://www.tumblr.com/someblog/123456789012/some-post"
+    );
+}
+
+#[test]
+fn test_bluesky_url() {
+    let url = Url::parse(
+        "https://bsky.app/profile/someone.bsky.social/post/3kabcdefghi2x?utm_source=share&ref_src=embed",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_bluesky_url(&Parser::new(), &url),
+        Some("https://bsky.app/profile/someone.bsky.social/post/3kabcdefghi2x".to_string())
+    );
+}
+
+#[test]
+fn test_mastodon_url() {
+    let mut input = String::from("Toot: https://mastodon.social/@someone/112233445566778899?utm_source=newsletter&utm_medium=email and https://fosstodon.org/@other@example.social/112233445566778899?utm_campaign=x");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Toot: https://mastodon.social/@someone/112233445566778899 and https://fosstodon.org/@other@example.social/112233445566778899"
+    );
+}
+
+#[test]
+fn test_mastodon_path_shape() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://social.example/@someone/112233445566778899?utm_source=x")
+        .unwrap();
+    assert_eq!(reports[0].provider, "mastodon");
+
+    // Profiles and non-numeric paths are not recognized as Mastodon
+    let url = Url::parse("https://example.com/@someone/about?utm_source=x").unwrap();
+    assert!(!MASTODON.matches(&url));
+    let url = Url::parse("https://example.com/someone/112233445566778899").unwrap();
+    assert!(!MASTODON.matches(&url));
+}
+
+#[test]
+fn test_booking_url() {
+    let mut input = String::from("Hotel: https://www.booking.com/hotel/de/some-hotel.html?aid=304142&label=gen173nr-1FCAEoggI46AdIM1gEaDuIAQGYAQe4ARfIAQzYAQHoAQH4AQuIAgGoAgO4AqWl&sid=0123456789abcdef0123456789abcdef&checkin=2024-06-01&checkout=2024-06-05&group_adults=2&group_children=1&age=7&no_rooms=1&srpvid=abc123&ucfs=1&srepoch=1700000000");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Hotel: https://www.booking.com/hotel/de/some-hotel.html?checkin=2024-06-01&checkout=2024-06-05&group_adults=2&group_children=1&age=7&no_rooms=1"
+    );
+}
+
+#[test]
+fn test_booking_url_conservative_keeps_partner_ids() {
+    let mut parser = Parser::new();
+    parser.provider_strictness("booking", Strictness::Conservative);
+    let url = Url::parse(
+        "https://www.booking.com/hotel/de/some-hotel.html?aid=304142&sid=abc&checkin=2024-06-01",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_booking_url(&parser, &url),
+        Some(
+            "https://www.booking.com/hotel/de/some-hotel.html?aid=304142&checkin=2024-06-01"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_airbnb_url() {
+    let mut input = String::from("Stay: https://www.airbnb.com/rooms/12345678?adults=2&children=1&infants=0&pets=1&check_in=2024-07-10&check_out=2024-07-14&source_impression_id=p3_1700000000_AbCdEf&federated_search_id=0123-4567&s=67&unique_share_id=89ab-cdef&viralityEntryPoint=1");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Stay: https://www.airbnb.com/rooms/12345678?adults=2&children=1&infants=0&pets=1&check_in=2024-07-10&check_out=2024-07-14"
+    );
+}
+
+#[test]
+fn test_expedia_url() {
+    let mut input = String::from("Trip: https://www.expedia.co.uk/Berlin-Hotels-Some-Hotel.h1234567.Hotel-Information?chkin=2024-08-01&chkout=2024-08-03&rm1=a2&regionId=536&pwaLob=wizard-hotel-pwa-v2&rfrr=HSR");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Trip: https://www.expedia.co.uk/Berlin-Hotels-Some-Hotel.h1234567.Hotel-Information?chkin=2024-08-01&chkout=2024-08-03&rm1=a2&regionId=536"
+    );
+}
+
+#[test]
+fn test_skyscanner_url() {
+    let mut input = String::from("Flight: https://www.skyscanner.net/transport/flights/lond/nyca/240901/240910/?adultsv2=2&childrenv2=5&cabinclass=economy&rtn=1&preferdirects=false&associateid=AFF_123&utm_source=partner");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Flight: https://www.skyscanner.net/transport/flights/lond/nyca/240901/240910/?adultsv2=2&childrenv2=5&cabinclass=economy&rtn=1&preferdirects=false"
+    );
+}
+
+#[test]
+fn test_travel_keep_list_mode_keeps_dates_and_guests() {
+    let mut parser = Parser::new();
+    for name in ["booking", "airbnb", "expedia"] {
+        parser.keep_list_mode(name, true);
+    }
+    let reports = parser
+        .parse_any_text(
+            "https://www.booking.com/hotel/de/x.html?checkin=2024-06-01&checkout=2024-06-05&group_adults=2&dest_type=city \
+             https://www.airbnb.de/rooms/1?check_in=2024-07-10&adults=3&foo=bar \
+             https://www.expedia.com/Hotel-Search?startDate=2024-08-01&endDate=2024-08-03&adults=2&sort=RECOMMENDED",
+        )
+        .unwrap();
+    let cleaned: Vec<&str> = reports.iter().map(|r| r.cleaned.as_str()).collect();
+    assert_eq!(
+        cleaned,
+        vec![
+            "https://www.booking.com/hotel/de/x.html?checkin=2024-06-01&checkout=2024-06-05&group_adults=2",
+            "https://www.airbnb.de/rooms/1?check_in=2024-07-10&adults=3",
+            "https://www.expedia.com/Hotel-Search?startDate=2024-08-01&endDate=2024-08-03&adults=2",
+        ]
+    );
+}
+
+#[test]
+fn test_bing_url() {
+    let mut input = String::from("Search: https://www.bing.com/search?q=rust%20url%20parser&form=QBLH&sp=-1&lq=0&pq=rust+url+pars&sc=10-13&qs=n&sk=&cvid=0123456789ABCDEF0123456789ABCDEF&first=11");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Search: https://www.bing.com/search?q=rust%20url%20parser&first=11"
+    );
+}
+
+#[test]
+fn test_bing_search_conservative() {
+    let mut parser = Parser::new();
+    parser.strictness(Strictness::Conservative);
+    let url = Url::parse("https://www.bing.com/search?q=rust&form=QBLH&pc=U531").unwrap();
+    assert_eq!(
+        parse_microsoft_url(&parser, &url),
+        Some("https://www.bing.com/search?q=rust&pc=U531".to_string())
+    );
+}
+
+#[test]
+fn test_bing_images_url() {
+    let url =
+        Url::parse("https://www.bing.com/images/search?q=cats&FORM=HDRSC2&view=detailV2").unwrap();
+    assert_eq!(
+        parse_microsoft_url(&Parser::new(), &url),
+        Some("https://www.bing.com/images/search?q=cats&view=detailV2".to_string())
+    );
+}
+
+#[test]
+fn test_msn_url() {
+    let mut input = String::from("News: https://www.msn.com/en-us/news/world/some-story/ar-AA1abcde?ocid=msedgntp&cvid=0123456789abcdef&ei=12");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "News: https://www.msn.com/en-us/news/world/some-story/ar-AA1abcde"
+    );
+}
+
+#[test]
+fn test_sharepoint_url() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("Doc: https://contoso.sharepoint.com/:w:/g/personal/someone_contoso_com/EAbCdEfGhIj?e=AbC123&CT=1700000000000&OR=ItemsView")
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://contoso.sharepoint.com/:w:/g/personal/someone_contoso_com/EAbCdEfGhIj"
+    );
+    assert_eq!(reports[0].provider, "microsoft");
+}
+
+#[test]
+fn test_onedrive_access_token_kept() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text(
+            "File: https://onedrive.live.com/redir?resid=ABC123!105&authkey=!AbCdEf&e=XyZ789",
+        )
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://onedrive.live.com/redir?resid=ABC123!105&authkey=!AbCdEf"
+    );
+    let kept: Vec<&Finding> = reports[0]
+        .findings
+        .iter()
+        .filter(|finding| finding.action == Action::Kept)
+        .collect();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].name, "authkey");
+    assert_eq!(kept[0].category, Category::Functional);
+}
+
+#[test]
+fn test_outlook_safelinks() {
+    let mut input = String::from("Mail: https://eur01.safelinks.protection.outlook.com/?url=https%3A%2F%2Fwww.bing.com%2Fsearch%3Fq%3Dtest%26form%3DQBLH&data=05%7C01%7Csomeone%40example.com%7C0123&sdata=AbCdEf%3D&reserved=0");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(input, "Mail: https://www.bing.com/search?q=test");
+}
+
+#[test]
+fn test_outlook_safelinks_to_file() {
+    let url = Url::parse(
+        "https://eur01.safelinks.protection.outlook.com/?url=file%3A%2F%2F%2Fetc%2Fpasswd&reserved=0",
+    )
+    .unwrap();
+    assert_eq!(parse_microsoft_url(&Parser::new(), &url), None);
+}
+
+#[test]
+fn test_teams_safelinks() {
+    let url = Url::parse("https://statics.teams.cdn.office.net/evergreen-assets/safelinks/1/atp-safelinks.html?url=https%3A%2F%2Fexample.com%2F&locale=en-us").unwrap();
+    assert_eq!(
+        parse_microsoft_url(&Parser::new(), &url),
+        Some("https://example.com/".to_string())
+    );
+}
+
+#[test]
+fn test_steam_linkfilter() {
+    let mut input = String::from("From Steam: https://steamcommunity.com/linkfilter/?u=https%3A%2F%2Fstore.steampowered.com%2Fapp%2F620%2FPortal_2%2F%3Fsnr%3D1_7_7_230_150_1");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "From Steam: https://store.steampowered.com/app/620/Portal_2/"
+    );
+}
+
+#[test]
+fn test_steam_linkfilter_to_data() {
+    let url = Url::parse(
+        "https://steamcommunity.com/linkfilter/?u=data%3Atext%2Fhtml%2C%3Cscript%3Ealert(1)%3C%2Fscript%3E",
+    )
+    .unwrap();
+    assert_eq!(parse_steam_linkfilter_url(&Parser::new(), &url), None);
+}
+
+#[test]
+fn test_steam_url() {
+    let mut input = String::from("Game: https://store.steampowered.com/app/620/Portal_2/?snr=1_7_7_230_150_1&curator_clanid=123 and https://steamcommunity.com/app/620/?snr=1_5_9__205");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Game: https://store.steampowered.com/app/620/Portal_2/ and https://steamcommunity.com/app/620/"
+    );
+}
+
+#[test]
+fn test_google_play_url() {
+    let mut input = String::from("App: https://play.google.com/store/apps/details?id=com.example.app&hl=en&referrer=utm_source%3Dnewsletter%26utm_medium%3Demail%26utm_campaign%3Dlaunch&pcampaignid=web_share");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "App: https://play.google.com/store/apps/details?id=com.example.app&hl=en"
+    );
+}
+
+#[test]
+fn test_google_play_referrer_findings() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("https://play.google.com/store/apps/details?id=com.example.app&referrer=utm_source%3Dnewsletter%26gclid%3Dabc123%26campaign%3Dlaunch")
+        .unwrap();
+    let findings: Vec<(&str, &str, Category)> = reports[0]
+        .findings
+        .iter()
+        .map(|f| (f.name.as_str(), f.value.as_str(), f.category))
+        .collect();
+    assert_eq!(
+        findings,
+        vec![
+            (
+                "referrer",
+                "utm_source=newsletter&gclid=abc123&campaign=launch",
+                Category::Referral
+            ),
+            ("utm_source", "newsletter", Category::Analytics),
+            ("gclid", "abc123", Category::Analytics),
+            ("campaign", "launch", Category::Referral),
+        ]
+    );
+}
+
+#[test]
+fn test_app_store_url() {
+    let mut input = String::from("App: https://apps.apple.com/us/app/some-app/id1234567890?pt=123456&ct=website&mt=8&uo=4 and https://itunes.apple.com/us/app/some-app/id1234567890?mt=8");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "App: https://apps.apple.com/us/app/some-app/id1234567890 and https://itunes.apple.com/us/app/some-app/id1234567890"
+    );
+}
+
+#[test]
+fn test_itch_io_url() {
+    let url =
+        Url::parse("https://someone.itch.io/some-game?ac=AbCdEf12&utm_source=twitter").unwrap();
+    assert_eq!(
+        parse_itch_io_url(&Parser::new(), &url),
+        Some("https://someone.itch.io/some-game".to_string())
+    );
+}
+
+#[test]
+fn test_youtube_short_forms() {
+    let mut input = String::from("Shorts: https://www.youtube.com/shorts/AbCdEfGhIjK?si=AbCdEfGh, live: https://www.youtube.com/live/AbCdEfGhIjK?si=AbCdEfGh&t=120, embed: https://www.youtube-nocookie.com/embed/AbCdEfGhIjK?start=30&si=AbCdEfGh");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Shorts: https://www.youtube.com/shorts/AbCdEfGhIjK, live: https://www.youtube.com/live/AbCdEfGhIjK?t=120, embed: https://www.youtube-nocookie.com/embed/AbCdEfGhIjK?start=30"
+    );
+}
+
+#[test]
+fn test_youtube_share_tokens() {
+    let mut parser = Parser::new();
+    parser.strictness(Strictness::Conservative);
+    let url =
+        Url::parse("https://youtu.be/dQw4w9WgXcQ?si=AbCdEfGh&pp=ygUEcnVzdA%3D%3D&t=42").unwrap();
+    assert_eq!(
+        parse_youtube_url(&parser, &url),
+        Some("https://youtu.be/dQw4w9WgXcQ?t=42".to_string())
+    );
+}
+
+#[test]
+fn test_youtube_keep_list_by_url_shape() {
+    let mut parser = Parser::new();
+    parser.keep_list_mode("youtube", true);
+    let reports = parser
+        .parse_any_text(
+            "https://www.youtube.com/watch?v=abc&list=PL123&index=2&t=42&foo=1 \
+             https://www.youtube.com/shorts/abc?t=42 \
+             https://www.youtube.com/embed/abc?autoplay=1&mute=1&foo=1 \
+             https://youtu.be/abc?t=42&v=other",
+        )
+        .unwrap();
+    let cleaned: Vec<&str> = reports.iter().map(|r| r.cleaned.as_str()).collect();
+    assert_eq!(
+        cleaned,
+        vec![
+            "https://www.youtube.com/watch?v=abc&list=PL123&index=2&t=42",
+            "https://www.youtube.com/shorts/abc",
+            "https://www.youtube.com/embed/abc?autoplay=1&mute=1",
+            "https://youtu.be/abc?t=42",
+        ]
+    );
+}
+
+#[test]
+fn test_youtube_attribution_link() {
+    let mut input = String::from("Video: https://www.youtube.com/attribution_link?a=AbCdEfGh&u=%2Fwatch%3Fv%3DdQw4w9WgXcQ%26feature%3Dshare%26t%3D42");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Video: https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42"
+    );
+
+    let mut parser = Parser::new();
+    parser.register_provider(
+        YouTube {
+            unwrap_attribution_links: false,
+        },
+        0,
+    );
+    let reports = parser
+        .parse_any_text("https://www.youtube.com/attribution_link?a=AbCdEfGh&u=%2Fwatch%3Fv%3Dabc%26feature%3Dshare&utm_source=x")
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.youtube.com/attribution_link?a=AbCdEfGh&u=%2Fwatch%3Fv%3Dabc%26feature%3Dshare"
+    );
+}
+
+#[test]
+fn test_youtube_attribution_link_to_other_site() {
+    let parser = Parser::new();
+    for link in [
+        "https://www.youtube.com/attribution_link?u=https%3A%2F%2Fevil.com%2F",
+        "https://www.youtube.com/attribution_link?u=%2F%2Fevil.com%2F",
+        "https://www.youtube.com/attribution_link?u=javascript%3Aalert(1)",
+    ] {
+        let url = Url::parse(link).unwrap();
+        assert_eq!(parse_youtube_url(&parser, &url), None);
+    }
+
+    let url = Url::parse(
+        "https://www.youtube.com/attribution_link?u=https%3A%2F%2Fyoutu.be%2Fabc%3Fsi%3Dxyz",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_youtube_url(&parser, &url),
+        Some("https://youtu.be/abc".to_string())
+    );
+}
+
+#[test]
+fn test_kept_params_keep_their_encoding() {
+    let mut input =
+        String::from("https://www.youtube.com/results?search_query=salt+%26+pepper%2Fherbs&si=abc");
+    assert!(replace_urls_in_place(&mut input).is_some());
+    assert_eq!(
+        input,
+        "https://www.youtube.com/results?search_query=salt+%26+pepper%2Fherbs"
+    );
+}
+
+#[test]
+fn test_twitter_intent_url() {
+    let mut input = String::from("Share: https://x.com/intent/tweet?text=Look&url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3Dabc%26si%3DAbCd&t=1&s=2&original_referer=https%3A%2F%2Fexample.com");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Share: https://x.com/intent/tweet?text=Look&url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3Dabc&t=1&s=2"
+    );
+}
+
+#[test]
+fn test_twitter_intent_findings() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text(
+            "https://twitter.com/intent/tweet?text=Hi&url=https%3A%2F%2Fexample.com%2F%3Fgclid%3Dabc",
+        )
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://twitter.com/intent/tweet?text=Hi&url=https%3A%2F%2Fexample.com%2F"
+    );
+    assert_eq!(reports[0].provider, "twitter");
+    assert_eq!(reports[0].findings[0].name, "gclid");
+    assert_eq!(reports[0].findings[0].provider, "google_ads");
+}
+
+#[test]
+fn test_twitter_intent_keeps_encoding_of_other_params() {
+    let url = Url::parse(
+        "https://x.com/intent/tweet?text=50%25%20off&url=https%3A%2F%2Fexample.com%2F%3Fgclid%3Dabc&via=shop",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_twitter_url(&Parser::new(), &url),
+        Some(
+            "https://x.com/intent/tweet?text=50%25%20off&url=https%3A%2F%2Fexample.com%2F&via=shop"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_twitter_search_url() {
+    let url = Url::parse("https://x.com/search?q=rust&src=typed_query&f=live&t=1").unwrap();
+    assert_eq!(
+        parse_twitter_url(&Parser::new(), &url),
+        Some("https://x.com/search?q=rust&f=live&t=1".to_string())
+    );
+}
+
+#[test]
+fn test_twitter_i_web_url() {
+    let url = Url::parse("https://x.com/i/web/status/1234567890?s=20&t=AbCdEf").unwrap();
+    assert_eq!(
+        parse_twitter_url(&Parser::new(), &url),
+        Some("https://x.com/i/web/status/1234567890".to_string())
+    );
+}
+
+#[test]
+fn test_instagram_igsh_removed() {
+    let mut input = String::from("Reel: https://www.instagram.com/reel/ABC123/?igsh=MWQ1ZGUxMzBkMA== and post https://www.instagram.com/p/ABC123/?img_index=3&igsh=MWQ1ZGUxMzBkMA==");
+    let result = replace_urls_in_place(&mut input);
+    assert!(result.is_some());
+    assert_eq!(
+        input,
+        "Reel: https://www.instagram.com/reel/ABC123/ and post https://www.instagram.com/p/ABC123/?img_index=3"
+    );
+}
+
+#[test]
+fn test_instagram_share_link() {
+    let parser = Parser::new();
+    let reports = parser
+        .parse_any_text("Reel: https://www.instagram.com/share/reel/BAbCdEfGh?igsh=abc")
+        .unwrap();
+    assert_eq!(
+        reports[0].cleaned,
+        "https://www.instagram.com/share/reel/BAbCdEfGh"
+    );
+    let resolution: Vec<&str> = reports[0]
+        .findings
+        .iter()
+        .filter(|finding| finding.action == Action::RequiresResolution)
+        .map(|finding| finding.value.as_str())
+        .collect();
+    assert_eq!(resolution, vec!["/share/reel/BAbCdEfGh"]);
+}
+
+#[test]
+fn test_instagram_keep_list_by_path_type() {
+    let mut parser = Parser::new();
+    parser.keep_list_mode("instagram", true);
+    let reports = parser
+        .parse_any_text(
+            "https://www.instagram.com/p/ABC/?img_index=2&foo=1 \
+             https://www.instagram.com/reel/ABC/?img_index=2 \
+             https://www.instagram.com/stories/someone/1234567890/?utm_source=ig_story_item_share",
+        )
+        .unwrap();
+    let cleaned: Vec<&str> = reports.iter().map(|r| r.cleaned.as_str()).collect();
+    assert_eq!(
+        cleaned,
+        vec![
+            "https://www.instagram.com/p/ABC/?img_index=2",
+            "https://www.instagram.com/reel/ABC/",
+            "https://www.instagram.com/stories/someone/1234567890/",
+        ]
+    );
+}
+
+#[test]
+fn test_threads_igsh_removed() {
+    let url = Url::parse("https://www.threads.net/@someone/post/AbCdEf?xmt=AQGz&igsh=abc").unwrap();
+    assert_eq!(
+        parse_facebook_url(&Parser::new(), &url),
+        Some("https://www.threads.net/@someone/post/AbCdEf".to_string())
+    );
+}
+
+#[test]
+fn test_replaced_builtin_keeps_its_place() {
+    let mut parser = Parser::new();
+    let names: Vec<String> = parser
+        .provider_names()
+        .iter()
+        .map(|n| n.to_string())
+        .collect();
+    parser
+        .register_provider(
+            YouTube {
+                unwrap_attribution_links: false,
+            },
+            0,
+        )
+        .register_provider(
+            TikTok {
+                normalize_paths: false,
+            },
+            0,
+        );
+    assert_eq!(parser.provider_names(), names);
+
+    let reports = parser
+        .parse_any_text(
+            "https://www.youtube.com/@channel/12345?si=x https://www.tiktok.com/@user/7234567890?_r=1",
+        )
+        .unwrap();
+    let cleaned: Vec<(&str, &str)> = reports
+        .iter()
+        .map(|r| (r.provider.as_str(), r.cleaned.as_str()))
+        .collect();
+    assert_eq!(
+        cleaned,
+        vec![
+            ("youtube", "https://www.youtube.com/@channel/12345"),
+            ("tiktok", "https://www.tiktok.com/@user/7234567890"),
+        ]
+    );
+}